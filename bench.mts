@@ -0,0 +1,30 @@
+import b from 'benny';
+import scanner from './dist/index.js';
+
+const repoDir = process.argv[2] ?? '.';
+const extensions = ['ts', 'js', 'json', 'md'];
+const globs = extensions.map((ext) => `**/*.${ext}`);
+
+await b.suite(
+  `Extension queries: ${repoDir}`,
+  b.add('walkRepoByExtension', () => {
+    scanner.walkRepoByExtension(repoDir, extensions);
+  }),
+  b.add('walkRepoGlobs', () => {
+    scanner.walkRepoGlobs(repoDir, globs);
+  }),
+  b.cycle(),
+  b.complete(),
+);
+
+await b.suite(
+  `Result marshaling: ${repoDir}`,
+  b.add('walkRepoGlob', () => {
+    scanner.walkRepoGlob(repoDir, '**');
+  }),
+  b.add('walkRepoGlobJson', () => {
+    JSON.parse(scanner.walkRepoGlobJson(repoDir, '**'));
+  }),
+  b.cycle(),
+  b.complete(),
+);
@@ -3,7 +3,659 @@
 
 /* auto-generated by NAPI-RS */
 
+export interface WalkOptions {
+  /**
+   * Report and match paths relative to the root of the enclosing git repository
+   * instead of `repoDir`. Falls back to `repoDir` when it isn't inside a repository.
+   */
+  repoRootRelative?: boolean
+  /**
+   * Names of directories to skip entirely, compared against the directory name.
+   * Cheaper than exclude globs for skipping e.g. `node_modules` or `target`.
+   */
+  pruneDirs?: Array<string>
+  /** Match globs ignoring case */
+  caseInsensitive?: boolean
+  /**
+   * Match globs against file names only, so `Makefile` matches `src/Makefile`.
+   * Globs containing `/` never match in this mode.
+   */
+  matchBasename?: boolean
+  /**
+   * Treat globs without wildcards naming a directory as everything
+   * inside of it, i.e. `src` as `src/**`
+   */
+  dirAsRecursive?: boolean
+  /**
+   * Return paths sorted lexically and deduplicated, instead of the walk order
+   * where files precede subdirectories
+   */
+  uniqueSorted?: boolean
+  /** Report symlinks as matches themselves. Symlinks are never followed. */
+  includeSymlinks?: boolean
+  /**
+   * Don't read `.gitmodules`, saving a file read per call for repositories
+   * known to have no submodules. Otherwise submodule contents are walked too.
+   */
+  skipSubmoduleCheck?: boolean
+  /**
+   * Set to `false` to skip sorting directory entries and get them in the order
+   * the file system yields them, which differs between platforms and file systems
+   */
+  sort?: boolean
+  /**
+   * Report and match paths relative to this directory, given relative to
+   * `repoDir` (or the repository root with `repoRootRelative`).
+   * Files outside of it are skipped.
+   */
+  relativeTo?: string
+  /**
+   * Skip files ending with any of these extensions, given with or without the
+   * leading dot. Compound ones like `min.js` are compared against the end
+   * of the file name, so `app.min.js` is skipped while `app.js` isn't.
+   */
+  excludeExtensions?: Array<string>
+  /**
+   * Name of a file, e.g. `package.json`, marking a boundary: subdirectories
+   * of a directory containing it aren't walked, while its own files still are
+   */
+  stopAtMarker?: string
+  /**
+   * Return paths sorted with digit runs compared as numbers, so `file2.json`
+   * precedes `file10.json`. Combined with `uniqueSorted`, paths are deduplicated too.
+   */
+  naturalSort?: boolean
+  /**
+   * Translate `@(a|b)` extended globs into `{a,b}` alternations. Other extglob
+   * forms, i.e. `?(..)`, `*(..)`, `+(..)` and `!(..)`, make globs invalid.
+   */
+  extglob?: boolean
+  /**
+   * Virtual location of the repository, e.g. `/mnt/overlay/repo`, which is prepended
+   * to paths before matching, so that globs can be written against it.
+   * Reported paths don't include it.
+   */
+  mountPrefix?: string
+  /**
+   * Slow the walk down on purpose, sleeping whenever more than this many entries,
+   * directories included, would be visited per second. Helps to spare shared
+   * storage like network filesystems.
+   */
+  maxEntriesPerSecond?: number
+  /**
+   * Honor `.ignore` files, the tool-agnostic counterpart of `.gitignore`.
+   * Enabled by default, as walks honored them before this option existed,
+   * so pass `false` to walk files they exclude.
+   */
+  useIgnoreFiles?: boolean
+  /** Honor `.rgignore` files of ripgrep as well */
+  useRgignore?: boolean
+  /**
+   * When `repoDir` is a linked git worktree without its own `.gitmodules`,
+   * exclude submodules listed in `.gitmodules` of the main working tree
+   */
+  linkedWorktree?: boolean
+  /**
+   * Make checked walks, e.g. `walkRepoGlobChecked`, throw `ConfigParse` for a malformed
+   * `.gitmodules` instead of walking submodules like regular directories.
+   * Other functions can't throw, so they ignore this.
+   */
+  strictSubmodules?: boolean
+  /**
+   * Skip zero-byte files, e.g. empty placeholders. Files whose size
+   * can't be read are kept.
+   */
+  skipEmpty?: boolean
+  /**
+   * Match in Unicode NFC form, so that e.g. `café.*` matches `café.txt` stored
+   * decomposed (NFD) as on macOS. Returned paths keep their form on disk.
+   */
+  unicodeNormalize?: boolean
+  /**
+   * Lowercase returned paths, e.g. for comparing results across filesystems,
+   * including streamed paths and paths in result objects.
+   * Matching stays case-sensitive, and `uniqueSorted` dedupes the lowercased paths.
+   */
+  lowercaseOutput?: boolean
+  /**
+   * Honor a `.scanignore` file in `repoDir`, written in `.gitignore` syntax,
+   * for scan exclusions independent of git. Files are filtered after being walked,
+   * so unlike with `pruneDirs`, directories it excludes are still descended into.
+   */
+  useScanignore?: boolean
+  /**
+   * Return paths with `\` separators on Windows, e.g. for passing them to Win32 APIs,
+   * except from `matchPaths`, which returns paths as given. Globs are still matched
+   * against `/`-separated paths. No-op on other platforms.
+   */
+  nativeSeparators?: boolean
+  /**
+   * Paths to leave out, e.g. results of a prior walk, compared exactly against walked
+   * `/`-separated paths, relative to `relativeTo` if given. Cheaper than exclude globs.
+   */
+  excludePaths?: Array<string>
+}
+export interface GlobSpec {
+  glob: string
+  /** Overrides `caseInsensitive` of `WalkOptions` for this glob */
+  caseInsensitive?: boolean
+  /** Prevent `*` and `?` from matching `/` */
+  literalSeparator?: boolean
+}
+/**
+ * Expands `{a,b}` alternations into all the concrete patterns they describe,
+ * e.g. `{a,b}/{c,d}` gives four patterns. Escaped braces and commas are kept escaped,
+ * and patterns with unbalanced braces are returned unchanged.
+ */
+export function expandBraces(pattern: string): Array<string>
+/**
+ * Returns submodule paths listed in `.gitmodules` of `repoDir`, throwing with
+ * the parse error when it's malformed. Walks silently ignore such files,
+ * so this helps to find out why submodules aren't excluded.
+ */
+export function parseGitmodules(repoDir: string): Array<string>
+/**
+ * Walks up from `startDir` to the closest directory containing `.git`, which is
+ * either a directory or a file as in submodules and worktrees. Returns `null`
+ * when the filesystem root is reached without finding one.
+ */
+export function findRepoRoot(startDir: string): string | null
 export function globToRegex(glob: string): string | null
-export function walkRepoGlob(repoDir: string, glob: string): Array<string>
-export function walkRepoGlobs(repoDir: string, globs: Array<string>): Array<string>
-export function walkRepoGlobsMap(repoDir: string, globsMap: Record<string, Array<string>>): Record<string, Array<string>>
+/**
+ * Filters virtual paths, e.g. `a::b::c` module paths, by `glob` written with
+ * the same `separator` (defaults to `/`). Unlike in the walk functions, `*` doesn't
+ * match the separator, while `**` matches any number of segments.
+ *
+ * Matching is done by substituting `separator` with `/`, so segments must not contain
+ * `/` themselves, and the separator can't be escaped. Every occurrence of `separator`
+ * in `glob` is a segment boundary, e.g. with `.`, `*.java` matches `Foo.java` as two
+ * segments, but not `com.Foo.java`. Separators containing any of the glob
+ * metacharacters `*?[]{}!\` would change the meaning of `glob`, so they throw
+ * `InvalidGlob`. Paths are returned unchanged.
+ */
+export function matchPaths(paths: Array<string>, glob: string, separator?: string | undefined | null, options?: WalkOptions | undefined | null): Array<string>
+/**
+ * Filters `paths`, e.g. from `git ls-tree` output, by `globs` the same way
+ * `walkRepoGlobs` matches walked files, without accessing the file system.
+ * Paths are expected to be `/`-separated, and `dirAsRecursive` is ignored.
+ * A leading `./` is stripped from paths, as walked paths never have one.
+ */
+export function matchPathsAgainstGlobs(paths: Array<string>, globs: Array<string>, options?: WalkOptions | undefined | null): Array<string>
+/**
+ * Returns `paths` naming existing files under `repoDir`, checking each one directly
+ * instead of walking. Like in walks, files inside submodules and `.git` are skipped,
+ * but ignore files aren't taken into account. Absolute paths and paths with `..`
+ * segments are dropped, as they could point outside of `repoDir`.
+ */
+export function filterExistingPaths(repoDir: string, paths: Array<string>): Array<string>
+/**
+ * Returns files matching `glob`. When `repoDir` points to a file
+ * rather than a directory, only that file is matched, by its name.
+ */
+export function walkRepoGlob(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Array<string>
+/**
+ * Same as `walkRepoGlob`, but throws `RepoNotFound` when `repoDir`
+ * is not a directory instead of returning an empty list.
+ */
+export function tryWalkRepoGlob(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Array<string>
+/**
+ * Same as `walkRepoGlob`, but throws `RepoNotFound` when `repoDir` doesn't exist
+ * and `InvalidGlob` when `glob` can't be parsed.
+ */
+export function walkRepoGlobChecked(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Array<string>
+/**
+ * Same as `walkRepoGlob`, but returns the paths as a JSON array string.
+ * For large results, a single `JSON.parse` is cheaper than converting
+ * every path into a JS string separately.
+ */
+export function walkRepoGlobJson(repoDir: string, glob: string, options?: WalkOptions | undefined | null): string
+/**
+ * Same as `walkRepoGlob`, but keeps only every `stride`-th match, e.g. the 100th,
+ * 200th and so on for a stride of 100, to sample large repositories.
+ * Matches are counted in the walk order.
+ */
+export function walkRepoGlobSampled(repoDir: string, glob: string, stride?: number | undefined | null, options?: WalkOptions | undefined | null): Array<string>
+export interface BlobMatch {
+  path: string
+  /** Hex id of the blob recorded in the git index, if the file is tracked */
+  oid?: string
+}
+export function walkRepoGlobOids(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Array<BlobMatch>
+export interface TreeMatch {
+  path: string
+  /**
+   * Blob size in bytes, set with `withSizes` unless the blob is missing
+   * from the object database, e.g. in partial clones
+   */
+  size?: number
+}
+/**
+ * Matches files of the tree at `rev`, e.g. `HEAD`, `main~2` or a tag, instead of
+ * the working tree. With `withSizes`, sizes are read from blob headers in the object
+ * database rather than by stat-ing files. As nothing is walked on disk, only options
+ * affecting matching apply. Throws `RepoNotFound` outside of a repository
+ * and `RevisionNotFound` when `rev` doesn't resolve to a tree.
+ */
+export function walkRepoGlobAt(repoDir: string, rev: string, glob: string, withSizes?: boolean | undefined | null, options?: WalkOptions | undefined | null): Array<TreeMatch>
+export interface TrackedMatch {
+  path: string
+  /** Whether the file is present in the git index, i.e. committed or staged */
+  tracked: boolean
+}
+/**
+ * Returns matched files tagged with whether git tracks them.
+ * Outside of a repository, every file is reported as untracked.
+ */
+export function walkRepoGlobTracked(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Array<TrackedMatch>
+export interface ExecutableMatch {
+  path: string
+  /**
+   * Whether the git index records the file as executable (mode 100755),
+   * regardless of its permissions in the working tree
+   */
+  gitExecutable: boolean
+}
+/**
+ * Returns matched files tagged with their executable bit from the git index.
+ * Untracked files, and every file outside of a repository, are reported as not executable.
+ */
+export function walkRepoGlobGitExecutable(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Array<ExecutableMatch>
+export interface SubmoduleMatch {
+  /** Relative to the containing submodule, or to `repoDir` in the main tree */
+  path: string
+  /** Path of the containing submodule as listed in `.gitmodules` */
+  submodule?: string
+}
+/**
+ * Walks into submodules as well, reporting their matches relative to the submodule root.
+ * The glob is still matched against paths relative to `repoDir`.
+ * Only submodules listed in the top-level `.gitmodules` are recognized.
+ */
+export function walkRepoGlobSubmodules(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Array<SubmoduleMatch>
+/**
+ * Returns matched files which are untracked, staged or modified according to git.
+ *
+ * Modifications are detected by comparing content hashes with the index,
+ * without applying git filters such as line ending conversion.
+ * Deleted files aren't reported, as there's nothing to walk.
+ */
+export function walkRepoGlobDirty(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Array<string>
+/**
+ * Returns unique directories containing matched files,
+ * with the empty string standing for the root directory.
+ */
+export function walkRepoGlobParents(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Array<string>
+/**
+ * Returns unique directories directly containing a file whose name matches `glob`,
+ * e.g. package directories for `package.json`. Unlike in `walkRepoGlobParents`,
+ * `glob` is matched against file names rather than paths.
+ */
+export function walkRepoDirsContaining(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Array<string>
+export interface AncestorsEntry {
+  path: string
+  /**
+   * Directories from the top-level one down to the file's parent,
+   * empty for files located in the root directory
+   */
+  ancestors: Array<string>
+}
+/**
+ * Returns matched files along with their ancestor directories,
+ * e.g. for creating intermediate nodes of a tree.
+ */
+export function walkRepoGlobAncestors(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Array<AncestorsEntry>
+/**
+ * Returns unique directories containing matched files at any depth, i.e. all
+ * ancestors of matches, with the empty string standing for the root directory.
+ */
+export function walkRepoGlobDirsWithMatches(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Array<string>
+export interface CaptureMatch {
+  path: string
+  /**
+   * Part of the path matched by the captured glob segment, missing
+   * when it would span several directories, e.g. `*` matching `a/b`
+   */
+  capture?: string
+}
+/**
+ * Returns matched files along with the part of their path matched by the glob
+ * segment at `captureSegment`, e.g. the package name for segment 1
+ * of `packages/*`, which helps to group results by package.
+ */
+export function walkRepoGlobCaptures(repoDir: string, glob: string, captureSegment: number, options?: WalkOptions | undefined | null): Array<CaptureMatch>
+export interface DepthEntry {
+  path: string
+  /** Number of path segments, i.e. 1 for files in the root directory */
+  depth: number
+}
+/** Returns matched files along with their depth */
+export function walkRepoGlobDepths(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Array<DepthEntry>
+/**
+ * Groups matched files by their top-level directory,
+ * with the empty string key standing for files in the root directory.
+ */
+export function walkRepoGlobByToplevel(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Record<string, Array<string>>
+export interface TreeNode {
+  name: string
+  /** Directories first, then files, each sorted by name */
+  children: Array<TreeNode>
+  isFile: boolean
+}
+/**
+ * Returns matched files as a tree of the top-level entries, ready to render.
+ * Directories are only included when they contain matches.
+ */
+export function walkRepoGlobTree(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Array<TreeNode>
+export interface FileEntry {
+  path: string
+  size: number
+  mtimeMs: number
+}
+/**
+ * Returns the most recently modified matched file of every directory,
+ * with ties broken by the lexicographically smallest path.
+ */
+export function walkRepoGlobNewestPerDir(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Array<FileEntry>
+/**
+ * Writes `{ path, size, mtimeMs }` of every matched file to `outPath` as JSON lines
+ * while walking, so memory use doesn't grow with the number of matches.
+ * `outPath` is truncated first, even when nothing matches or `glob` is invalid.
+ * Returns the number of lines written.
+ */
+export function walkRepoGlobToFile(repoDir: string, glob: string, outPath: string, options?: WalkOptions | undefined | null): number
+/**
+ * Calls `callback` with every matched path as soon as it's found, and once more
+ * with `null` when the walk is done. The walk runs on a separate thread and pauses
+ * while `bufferSize` paths (256 by default) are waiting to be passed to `callback`.
+ * Paths come in walk order, so `uniqueSorted` and `naturalSort` have no effect.
+ */
+export function walkRepoGlobStream(repoDir: string, glob: string, callback: (err: Error | null, path: string | null) => void, bufferSize?: number | undefined | null, options?: WalkOptions | undefined | null): void
+/**
+ * Like `walkRepoGlobStream`, but calls `callback` with arrays of up to `batchSize`
+ * paths (64 by default) to cut down on calls into JS. A batch is passed on as soon
+ * as the walk has no more paths ready, so it can be shorter than `batchSize`.
+ */
+export function walkRepoGlobStreamBatched(repoDir: string, glob: string, callback: (err: Error | null, paths: string[] | null) => void, batchSize?: number | undefined | null, bufferSize?: number | undefined | null, options?: WalkOptions | undefined | null): void
+export interface ScanOptions {
+  /** Files matching any of these globs are returned */
+  globs: Array<string>
+  /** Files matching any of these globs are left out, even if matched by `globs` */
+  excludes?: Array<string>
+  /** Number of visited files between progress calls, 1000 by default */
+  progressInterval?: number
+  walk?: WalkOptions
+}
+export interface ScanProgress {
+  /** Files visited so far, matched or not */
+  visited: number
+  matched: number
+}
+export interface ScanResult {
+  paths: Array<string>
+  visited: number
+  /** Whether the scan was aborted, in which case `paths` holds the matches found until then */
+  cancelled: boolean
+}
+/**
+ * Walks on the libuv thread pool and resolves with files matching `globs` but not `excludes`.
+ *
+ * Throws `RepoNotFound` when `repoDir` doesn't exist and `InvalidGlob` for any invalid glob,
+ * before the walk starts.
+ *
+ * `progress` is called with counts every `progressInterval` visited files. Calls are
+ * skipped while the previous one is still waiting for the main thread, so they don't
+ * slow the walk down.
+ *
+ * Aborting `signal` stops the walk at the next file, and the promise resolves with the
+ * matches found so far and `cancelled` set, rather than rejecting. No progress calls are
+ * made after the abort, but one made just before it may still arrive afterwards.
+ */
+export function scan(repoDir: string, options: ScanOptions, progress?: ((progress: ScanProgress) => void) | undefined | null, signal?: AbortSignal | undefined | null): Promise<ScanResult>
+/**
+ * Returns a hex digest of the sorted matched paths, which stays the same until
+ * a matched file is added or removed. With `includeMtimes`, modification times
+ * are hashed as well, so that modified files change the digest too.
+ */
+export function fingerprintRepoGlob(repoDir: string, glob: string, includeMtimes?: boolean | undefined | null, options?: WalkOptions | undefined | null): string
+export interface LinkEntry {
+  path: string
+  /** Target of the symlink as written, unset for regular files */
+  linkTarget?: string
+}
+/**
+ * Returns matched files along with symlink targets,
+ * which requires the `includeSymlinks` option to find any.
+ */
+export function walkRepoGlobLinkTargets(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Array<LinkEntry>
+/**
+ * Calls `mapper` with `{ path, size, mtimeMs }` of every matched file
+ * and collects its non-null results.
+ *
+ * The walk itself completes before the first call, as JS can only be called
+ * from the main thread. Every call crosses the N-API boundary, which is
+ * noticeably slower than the specialized walk functions for large repos.
+ */
+export function walkRepoGlobMapJs(repoDir: string, glob: string, mapper: (entry: FileEntry) => unknown, options?: WalkOptions | undefined | null): Array<unknown>
+/**
+ * Returns matched files ordered by keys computed with `sortKey`, which is called
+ * once per file rather than per comparison. Numeric keys precede string keys,
+ * and files with equal keys keep the walk order.
+ */
+export function walkRepoGlobSortedBy(repoDir: string, glob: string, sortKey: (path: string) => number | string, options?: WalkOptions | undefined | null): Array<string>
+export interface ReadOptions {
+  /**
+   * Total number of bytes to read across all files. Once exhausted,
+   * reading stops and the matches found so far are returned.
+   */
+  maxTotalBytes?: number
+  /**
+   * Number of files read concurrently, each by its own thread. Defaults to 1,
+   * i.e. sequential reads. Files failing to open, e.g. because of exceeding
+   * the OS limit of open files, are treated as non-matching.
+   */
+  maxOpenFiles?: number
+  /** Files larger than this many bytes aren't read at all */
+  maxSize?: number
+  /**
+   * Only the first this many bytes of each file are read, e.g. to look for
+   * a header without loading whole bundles. Only these count towards `maxTotalBytes`.
+   */
+  maxReadBytes?: number
+}
+/** Returns matched files containing `needle` literally. */
+export function walkRepoGlobGrep(repoDir: string, glob: string, needle: string, readOptions?: ReadOptions | undefined | null, options?: WalkOptions | undefined | null): Array<string>
+/**
+ * Returns matched files whose first line starts with `prefix`, e.g. `#!/bin/sh`
+ * to find scripts. Only the first bytes of each file are read.
+ */
+export function walkRepoGlobShebang(repoDir: string, glob: string, prefix: string, options?: WalkOptions | undefined | null): Array<string>
+export interface FirstLine {
+  path: string
+  /**
+   * Without the line break, missing for binary and unreadable files.
+   * Lines longer than 1024 bytes are cut.
+   */
+  firstLine?: string
+}
+/**
+ * Returns matched files along with their first lines, e.g. shebangs or doctypes
+ * for language detection. Files are read only up to the first line break.
+ */
+export function walkRepoGlobFirstLines(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Array<FirstLine>
+export interface FileContent {
+  path: string
+  /** Missing when the file exceeds `maxSize`, isn't valid UTF-8 or can't be read */
+  content?: string
+}
+/**
+ * Returns matched files along with their contents, e.g. to collect
+ * all `package.json` files at once. Files cut short by `maxTotalBytes`
+ * or `maxReadBytes` have no content either.
+ */
+export function walkRepoGlobRead(repoDir: string, glob: string, readOptions?: ReadOptions | undefined | null, options?: WalkOptions | undefined | null): Array<FileContent>
+export function walkRepoGlobs(repoDir: string, globs: Array<string>, options?: WalkOptions | undefined | null): Array<string>
+/**
+ * Same as `walkRepoGlobs`, but throws `RepoNotFound` when `repoDir` doesn't exist
+ * and `InvalidGlob` when none of `globs` can be parsed. Invalid globs given along
+ * with valid ones are still skipped.
+ */
+export function walkRepoGlobsChecked(repoDir: string, globs: Array<string>, options?: WalkOptions | undefined | null): Array<string>
+/**
+ * Same as `walkRepoGlobs`, but reads globs from `globsFile`, one per line.
+ * Like in `.gitignore`, blank lines and lines starting with `#` are skipped,
+ * and `\#` stands for a literal `#`.
+ */
+export function walkRepoGlobsFromFile(repoDir: string, globsFile: string, options?: WalkOptions | undefined | null): Array<string>
+/**
+ * Same as `walk_repo_glob`, but only walks the given subdirectories of `repo_dir`,
+ * e.g. specific packages of a monorepo. Paths are still relative to `repo_dir`.
+ */
+export function walkRepoSubdirsGlob(repoDir: string, subdirs: Array<string>, glob: string, options?: WalkOptions | undefined | null): Array<string>
+/** Same as `walk_repo_globs`, but every glob carries its own matching flags */
+export function walkRepoGlobsOpts(repoDir: string, specs: Array<GlobSpec>, options?: WalkOptions | undefined | null): Array<string>
+/**
+ * Counts files matched by each glob. Duplicate globs share a single counter,
+ * and a file matching several globs is counted once for each of them.
+ */
+export function walkRepoGlobsCounts(repoDir: string, globs: Array<string>, options?: WalkOptions | undefined | null): Record<string, number>
+export interface UnmatchedReport {
+  paths: Array<string>
+  /** Valid globs which matched no files, in the order given */
+  unmatchedGlobs: Array<string>
+}
+/**
+ * Same as `walkRepoGlobs`, but also reports globs which never matched,
+ * helping to catch patterns that silently never fire. Invalid globs
+ * aren't reported, as they're skipped anyway.
+ */
+export function walkRepoGlobsUnmatched(repoDir: string, globs: Array<string>, options?: WalkOptions | undefined | null): UnmatchedReport
+export interface SuggestedPaths {
+  paths: Array<string>
+  /**
+   * Up to 10 files whose names match the last segment of the glob,
+   * filled only when nothing matched the glob itself
+   */
+  suggestions: Array<string>
+}
+/**
+ * Same as `walkRepoGlob`, but when nothing matches, suggests files that would
+ * match if only names were compared, e.g. `lib/index.ts` for `src/index.ts`.
+ */
+export function walkRepoGlobWithSuggestions(repoDir: string, glob: string, options?: WalkOptions | undefined | null): SuggestedPaths
+export interface GlobMask {
+  path: string
+  /** Bit `i` is set when `globs[i]` matched the path */
+  mask: number
+}
+export function walkRepoGlobsBitset(repoDir: string, globs: Array<string>, options?: WalkOptions | undefined | null): Array<GlobMask>
+export interface GlobPartition {
+  matched: Array<string>
+  unmatched: Array<string>
+}
+/**
+ * Splits all walked files into those matching any of `globs` and the rest
+ * in a single traversal, e.g. for finding files not covered by any rule.
+ */
+export function walkRepoGlobsPartition(repoDir: string, globs: Array<string>, options?: WalkOptions | undefined | null): GlobPartition
+export function walkRepoIncludeExclude(repoDir: string, include: Array<string>, exclude: Array<string>, options?: WalkOptions | undefined | null): Array<string>
+/**
+ * Walks files not excluded by `patterns` processed in order, where
+ * `!`-prefixed patterns re-include files excluded by earlier ones.
+ * Like in `.gitignore`, the last matching pattern wins, patterns without
+ * a slash match at any depth, and a leading `/` anchors a pattern to the root.
+ */
+export function walkRepoGitignoreStyle(repoDir: string, patterns: Array<string>, options?: WalkOptions | undefined | null): Array<string>
+/**
+ * Same as `walkRepoGitignoreStyle`, but reads patterns from `patternFiles` in order,
+ * so that later files override earlier ones, e.g. a base ruleset and a local one.
+ * Files use the format of `walkRepoGlobsFromFile`. Missing files throw `IoError`,
+ * unless `skipMissing` is set.
+ */
+export function walkRepoGlobFiles(repoDir: string, patternFiles: Array<string>, skipMissing?: boolean | undefined | null, options?: WalkOptions | undefined | null): Array<string>
+/**
+ * Walks files matching a subset of git pathspec syntax.
+ *
+ * Like in git, `*` matches `/` unless `:(glob)` magic is used, and a pathspec
+ * matching a directory matches everything inside of it. When only exclusions
+ * are given, every other file is included.
+ */
+export function walkRepoPathspec(repoDir: string, pathspecs: Array<string>, options?: WalkOptions | undefined | null): Array<string>
+export function walkRepoGlobsMap(repoDir: string, globsMap: Record<string, Array<string>>, options?: WalkOptions | undefined | null): Record<string, Array<string>>
+export interface CountedPaths {
+  count: number
+  paths: Array<string>
+}
+/** Same as `walkRepoGlobsMap`, but also returns the number of paths per key. */
+export function walkRepoGlobsMapCounted(repoDir: string, globsMap: Record<string, Array<string>>, options?: WalkOptions | undefined | null): Record<string, CountedPaths>
+export interface CappedPaths {
+  paths: Array<string>
+  /** Whether more paths matched than `maxPerKey` */
+  truncated: boolean
+}
+/**
+ * Same as `walkRepoGlobsMap`, but collects at most `maxPerKey` paths per key,
+ * keeping memory use bounded when a glob matches far more files than expected.
+ * The walk continues for keys which haven't reached the limit.
+ */
+export function walkRepoGlobsMapCapped(repoDir: string, globsMap: Record<string, Array<string>>, maxPerKey: number, options?: WalkOptions | undefined | null): Record<string, CappedPaths>
+export interface IndexedMatch {
+  key: string
+  /** Position of the matched glob in the key's list */
+  globIndex: number
+  path: string
+}
+/**
+ * Reports which glob of which key matched each path, to help debugging
+ * `walkRepoGlobsMap` configs. A path is reported once for every matching glob,
+ * with keys in sorted order. Invalid globs are skipped without shifting indices.
+ */
+export function walkRepoGlobsMapIndexed(repoDir: string, globsMap: Record<string, Array<string>>, options?: WalkOptions | undefined | null): Array<IndexedMatch>
+export interface GlobDiagnostic {
+  glob: string
+  error: string
+}
+/**
+ * Lists the globs of each key which `walkRepoGlobsMap` would skip as invalid
+ * with these options, without walking anything. Keys with valid globs only are left out.
+ */
+export function validateGlobsMap(globsMap: Record<string, Array<string>>, options?: WalkOptions | undefined | null): Record<string, Array<GlobDiagnostic>>
+/**
+ * Groups files by extension, e.g. `ts` or `.ts`, using set lookups instead of globs.
+ * Files having none of the `extensions` are skipped.
+ */
+export function walkRepoByExtension(repoDir: string, extensions: Array<string>, options?: WalkOptions | undefined | null): Record<string, Array<string>>
+/**
+ * Returns the distinct extensions of matched files, sorted and without the dot.
+ * Only the last extension counts, e.g. `gz` for `a.tar.gz`, and dotfiles such
+ * as `.gitignore` have none. With `caseInsensitive` or `lowercaseOutput`,
+ * extensions are lowercased.
+ */
+export function walkRepoGlobExtensions(repoDir: string, glob: string, options?: WalkOptions | undefined | null): Array<string>
+export interface RepoSize {
+  files: number
+  dirs: number
+}
+/**
+ * Counts files and directories to be walked without matching or collecting paths,
+ * e.g. for estimating the cost of a scan. The root directory itself isn't counted.
+ */
+export function estimateRepoSize(repoDir: string, options?: WalkOptions | undefined | null): RepoSize
+export interface WalkDiff {
+  /** Paths present in `b` only */
+  added: Array<string>
+  /** Paths present in `a` only */
+  removed: Array<string>
+}
+/** Compares two walk results, returning sorted path lists. */
+export function diffWalks(a: Array<string>, b: Array<string>): WalkDiff
+/**
+ * Walks one repository multiple times, reading `.gitmodules` only once.
+ * Changes to it made afterwards aren't picked up.
+ */
+export class RepoScanner {
+  constructor(repoDir: string, options?: WalkOptions | undefined | null)
+  /** Same as `walkRepoGlob` with the scanner's options */
+  walkGlob(glob: string): Array<string>
+  /** Same as `walkRepoGlobs` with the scanner's options */
+  walkGlobs(globs: Array<string>): Array<string>
+}
@@ -1,296 +1,6882 @@
 #![deny(clippy::all)]
 
+use napi::bindgen_prelude::AsyncTask;
+use napi::threadsafe_function::{
+  ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode,
+};
+use napi::{Env, JsFunction, JsObject, Task, ValueType};
 use napi_derive::napi;
 
+use std::borrow::Cow;
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::{BufRead, BufWriter, Read, Write};
+use std::path::{Component, Path, PathBuf};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver};
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
 
 use globset::Glob;
 
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct WalkOptions {
+  /// Report and match paths relative to the root of the enclosing git repository
+  /// instead of `repoDir`. Falls back to `repoDir` when it isn't inside a repository.
+  pub repo_root_relative: Option<bool>,
+  /// Names of directories to skip entirely, compared against the directory name.
+  /// Cheaper than exclude globs for skipping e.g. `node_modules` or `target`.
+  pub prune_dirs: Option<Vec<String>>,
+  /// Match globs ignoring case
+  pub case_insensitive: Option<bool>,
+  /// Match globs against file names only, so `Makefile` matches `src/Makefile`.
+  /// Globs containing `/` never match in this mode.
+  pub match_basename: Option<bool>,
+  /// Treat globs without wildcards naming a directory as everything
+  /// inside of it, i.e. `src` as `src/**`
+  pub dir_as_recursive: Option<bool>,
+  /// Return paths sorted lexically and deduplicated, instead of the walk order
+  /// where files precede subdirectories
+  pub unique_sorted: Option<bool>,
+  /// Report symlinks as matches themselves. Symlinks are never followed.
+  pub include_symlinks: Option<bool>,
+  /// Don't read `.gitmodules`, saving a file read per call for repositories
+  /// known to have no submodules. Otherwise submodule contents are walked too.
+  pub skip_submodule_check: Option<bool>,
+  /// Set to `false` to skip sorting directory entries and get them in the order
+  /// the file system yields them, which differs between platforms and file systems
+  pub sort: Option<bool>,
+  /// Report and match paths relative to this directory, given relative to
+  /// `repoDir` (or the repository root with `repoRootRelative`).
+  /// Files outside of it are skipped.
+  pub relative_to: Option<String>,
+  /// Skip files ending with any of these extensions, given with or without the
+  /// leading dot. Compound ones like `min.js` are compared against the end
+  /// of the file name, so `app.min.js` is skipped while `app.js` isn't.
+  pub exclude_extensions: Option<Vec<String>>,
+  /// Name of a file, e.g. `package.json`, marking a boundary: subdirectories
+  /// of a directory containing it aren't walked, while its own files still are
+  pub stop_at_marker: Option<String>,
+  /// Return paths sorted with digit runs compared as numbers, so `file2.json`
+  /// precedes `file10.json`. Combined with `uniqueSorted`, paths are deduplicated too.
+  pub natural_sort: Option<bool>,
+  /// Translate `@(a|b)` extended globs into `{a,b}` alternations. Other extglob
+  /// forms, i.e. `?(..)`, `*(..)`, `+(..)` and `!(..)`, make globs invalid.
+  pub extglob: Option<bool>,
+  /// Virtual location of the repository, e.g. `/mnt/overlay/repo`, which is prepended
+  /// to paths before matching, so that globs can be written against it.
+  /// Reported paths don't include it.
+  pub mount_prefix: Option<String>,
+  /// Slow the walk down on purpose, sleeping whenever more than this many entries,
+  /// directories included, would be visited per second. Helps to spare shared
+  /// storage like network filesystems.
+  pub max_entries_per_second: Option<u32>,
+  /// Honor `.ignore` files, the tool-agnostic counterpart of `.gitignore`.
+  /// Enabled by default, as walks honored them before this option existed,
+  /// so pass `false` to walk files they exclude.
+  pub use_ignore_files: Option<bool>,
+  /// Honor `.rgignore` files of ripgrep as well
+  pub use_rgignore: Option<bool>,
+  /// When `repoDir` is a linked git worktree without its own `.gitmodules`,
+  /// exclude submodules listed in `.gitmodules` of the main working tree
+  pub linked_worktree: Option<bool>,
+  /// Make checked walks, e.g. `walkRepoGlobChecked`, throw `ConfigParse` for a malformed
+  /// `.gitmodules` instead of walking submodules like regular directories.
+  /// Other functions can't throw, so they ignore this.
+  pub strict_submodules: Option<bool>,
+  /// Skip zero-byte files, e.g. empty placeholders. Files whose size
+  /// can't be read are kept.
+  pub skip_empty: Option<bool>,
+  /// Match in Unicode NFC form, so that e.g. `café.*` matches `café.txt` stored
+  /// decomposed (NFD) as on macOS. Returned paths keep their form on disk.
+  pub unicode_normalize: Option<bool>,
+  /// Lowercase returned paths, e.g. for comparing results across filesystems,
+  /// including streamed paths and paths in result objects.
+  /// Matching stays case-sensitive, and `uniqueSorted` dedupes the lowercased paths.
+  pub lowercase_output: Option<bool>,
+  /// Honor a `.scanignore` file in `repoDir`, written in `.gitignore` syntax,
+  /// for scan exclusions independent of git. Files are filtered after being walked,
+  /// so unlike with `pruneDirs`, directories it excludes are still descended into.
+  pub use_scanignore: Option<bool>,
+  /// Return paths with `\` separators on Windows, e.g. for passing them to Win32 APIs,
+  /// except from `matchPaths`, which returns paths as given. Globs are still matched
+  /// against `/`-separated paths. No-op on other platforms.
+  pub native_separators: Option<bool>,
+  /// Paths to leave out, e.g. results of a prior walk, compared exactly against walked
+  /// `/`-separated paths, relative to `relativeTo` if given. Cheaper than exclude globs.
+  pub exclude_paths: Option<Vec<String>>,
+}
+
+#[napi(object)]
+#[derive(Clone)]
+pub struct GlobSpec {
+  pub glob: String,
+  /// Overrides `caseInsensitive` of `WalkOptions` for this glob
+  pub case_insensitive: Option<bool>,
+  /// Prevent `*` and `?` from matching `/`
+  pub literal_separator: Option<bool>,
+}
+
+/// Error codes of the checked functions, available to JS as `error.code`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanError {
+  InvalidGlob,
+  RepoNotFound,
+  IoError,
+  ConfigParse,
+  RevisionNotFound,
+}
+
+impl AsRef<str> for ScanError {
+  fn as_ref(&self) -> &str {
+    match self {
+      ScanError::InvalidGlob => "InvalidGlob",
+      ScanError::RepoNotFound => "RepoNotFound",
+      ScanError::IoError => "IoError",
+      ScanError::ConfigParse => "ConfigParse",
+      ScanError::RevisionNotFound => "RevisionNotFound",
+    }
+  }
+}
+
+fn scan_error(code: ScanError, reason: String) -> napi::Error<ScanError> {
+  napi::Error::new(code, reason)
+}
+
+fn check_repo_dir(repo_dir: &str) -> napi::Result<(), ScanError> {
+  if Path::new(repo_dir).exists() {
+    Ok(())
+  } else {
+    let reason = format!("No such file or directory: {}", repo_dir);
+    Err(scan_error(ScanError::RepoNotFound, reason))
+  }
+}
+
+fn check_globs<S: AsRef<str>>(globs: &[S]) -> napi::Result<(), ScanError> {
+  for glob in globs {
+    if let Err(err) = Glob::new(glob.as_ref()) {
+      return Err(scan_error(ScanError::InvalidGlob, err.to_string()));
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+fn make_fixture(files: &[&str]) -> tempfile::TempDir {
+  let dir = tempfile::tempdir().unwrap();
+  for file in files {
+    let path = dir.path().join(file);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, "").unwrap();
+  }
+  dir
+}
+
+/// Globs are always matched against `/`-separated paths,
+/// so patterns written on Unix work on Windows as well.
+fn to_slash_path(path: &Path) -> Option<Cow<'_, str>> {
+  let path = path.to_str()?;
+  if cfg!(windows) {
+    Some(Cow::Owned(path.replace('\\', "/")))
+  } else {
+    Some(Cow::Borrowed(path))
+  }
+}
+
+fn join_slash_path<'a>(prefix: &str, path: &'a str) -> Cow<'a, str> {
+  if prefix.is_empty() {
+    Cow::Borrowed(path)
+  } else {
+    Cow::Owned(format!("{}/{}", prefix, path))
+  }
+}
+
+/// Drops the extended-length prefix of Windows paths, so that `\\?\C:\repo`
+/// and `\\?\UNC\server\share` turn into `C:\repo` and `\\server\share`
+fn simplify_verbatim_path(path: &str) -> Cow<'_, str> {
+  if let Some(rest) = path.strip_prefix(r"\\?\UNC\") {
+    Cow::Owned(format!(r"\\{}", rest))
+  } else if let Some(rest) = path.strip_prefix(r"\\?\") {
+    Cow::Borrowed(rest)
+  } else {
+    Cow::Borrowed(path)
+  }
+}
+
+#[test]
+fn test_simplify_verbatim_path() {
+  assert_eq!(simplify_verbatim_path(r"\\?\C:\repo\a"), r"C:\repo\a");
+  assert_eq!(
+    simplify_verbatim_path(r"\\?\UNC\server\share\repo"),
+    r"\\server\share\repo"
+  );
+  assert_eq!(simplify_verbatim_path(r"C:\repo"), r"C:\repo");
+  assert_eq!(simplify_verbatim_path("/home/repo"), "/home/repo");
+}
+
+/// Same as `Path::strip_prefix`, but on Windows it also succeeds
+/// when only one of the paths has the extended-length `\\?\` prefix
+fn strip_path_prefix<'a>(path: &'a Path, prefix: &Path) -> Option<Cow<'a, Path>> {
+  if let Ok(rest) = path.strip_prefix(prefix) {
+    return Some(Cow::Borrowed(rest));
+  }
+
+  if !cfg!(windows) {
+    return None;
+  }
+
+  let path = simplify_verbatim_path(path.to_str()?);
+  let prefix = simplify_verbatim_path(prefix.to_str()?);
+  let rest = Path::new(path.as_ref())
+    .strip_prefix(prefix.as_ref())
+    .ok()?;
+  Some(Cow::Owned(rest.to_path_buf()))
+}
+
+#[cfg(windows)]
+#[test]
+fn test_strip_path_prefix_verbatim() {
+  let path = Path::new(r"\\?\C:\repo\src\main.rs");
+  let rest = strip_path_prefix(path, Path::new(r"C:\repo")).unwrap();
+  assert_eq!(rest, Path::new(r"src\main.rs"));
+
+  let path = Path::new(r"\\server\share\repo\src\main.rs");
+  let rest = strip_path_prefix(path, Path::new(r"\\?\UNC\server\share\repo")).unwrap();
+  assert_eq!(rest, Path::new(r"src\main.rs"));
+
+  assert!(strip_path_prefix(path, Path::new(r"C:\repo")).is_none());
+}
+
+#[cfg(windows)]
+#[test]
+fn test_walk_repo_glob_verbatim_repo_dir() {
+  let fixture = make_fixture(&["src/config.json", "config.json"]);
+  let repo = fixture.path().canonicalize().unwrap();
+  let repo = repo.to_str().unwrap().to_string();
+  assert!(repo.starts_with(r"\\?\"));
+  let paths = walk_repo_glob(repo.clone(), "src/*.json".to_string(), None);
+  assert_eq!(paths, vec!["src/config.json".to_string()]);
+
+  let deep = format!(r"{}\{}", repo, ["nested"; 40].join(r"\"));
+  std::fs::create_dir_all(&deep).unwrap();
+  std::fs::write(format!(r"{}\deep.json", deep), "").unwrap();
+  let paths = walk_repo_glob(repo, "**/deep.json".to_string(), None);
+  assert_eq!(paths.len(), 1);
+  assert!(paths[0].len() > 260);
+}
+
+fn is_literal_glob(glob: &str) -> bool {
+  !glob.contains(['*', '?', '[', ']', '{', '}', '\\'])
+}
+
+fn compile_glob(repo_dir: &str, glob: &str, options: &WalkOptions) -> Option<Glob> {
+  let spec = GlobSpec {
+    glob: glob.to_string(),
+    case_insensitive: None,
+    literal_separator: None,
+  };
+  compile_glob_spec(repo_dir, &spec, options)
+}
+
+/// Translates `@(a|b)` groups into `{a,b}`, returning `None` for
+/// extglob forms which can't be expressed with `globset` syntax
+fn translate_extglob(glob: &str) -> Option<String> {
+  let mut result = String::with_capacity(glob.len());
+  let mut chars = glob.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '\\' => {
+        result.push(c);
+        result.extend(chars.next());
+      }
+      '@' | '?' | '*' | '+' | '!' if chars.peek() == Some(&'(') => {
+        if c != '@' {
+          return None;
+        }
+
+        chars.next();
+        let mut depth = 1;
+        let mut group = String::new();
+        for c in chars.by_ref() {
+          match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+          }
+          if depth == 0 {
+            break;
+          }
+          group.push(c);
+        }
+
+        if depth != 0 {
+          return None;
+        }
+
+        let mut alternatives = vec![];
+        let mut alternative = String::new();
+        let mut depth = 0;
+        for c in group.chars() {
+          match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            '|' if depth == 0 => {
+              alternatives.push(translate_extglob(&alternative)?);
+              alternative.clear();
+              continue;
+            }
+            _ => {}
+          }
+          alternative.push(c);
+        }
+        alternatives.push(translate_extglob(&alternative)?);
+
+        result.push('{');
+        result.push_str(&alternatives.join(","));
+        result.push('}');
+      }
+      _ => result.push(c),
+    }
+  }
+  Some(result)
+}
+
+#[test]
+fn test_translate_extglob() {
+  assert_eq!(
+    translate_extglob("@(foo|bar).json").as_deref(),
+    Some("{foo,bar}.json")
+  );
+  assert_eq!(
+    translate_extglob("src/@(a|b@(c|d))/*.ts").as_deref(),
+    Some("src/{a,b{c,d}}/*.ts")
+  );
+  assert_eq!(
+    translate_extglob("*.@(js|ts)x").as_deref(),
+    Some("*.{js,ts}x")
+  );
+  assert_eq!(translate_extglob(r"\@(a|b)").as_deref(), Some(r"\@(a|b)"));
+  assert_eq!(translate_extglob("**/*.json").as_deref(), Some("**/*.json"));
+  assert_eq!(translate_extglob("!(foo).json"), None);
+  assert_eq!(translate_extglob("+(a|b)"), None);
+  assert_eq!(translate_extglob("@(a|b"), None);
+}
+
+/// Expands `{a,b}` alternations into all the concrete patterns they describe,
+/// e.g. `{a,b}/{c,d}` gives four patterns. Escaped braces and commas are kept escaped,
+/// and patterns with unbalanced braces are returned unchanged.
+#[napi]
+pub fn expand_braces(pattern: String) -> Vec<String> {
+  expand_brace_groups(&pattern)
+}
+
+fn expand_brace_groups(pattern: &str) -> Vec<String> {
+  let Some((open, close)) = find_brace_group(pattern) else {
+    return vec![pattern.to_string()];
+  };
+
+  let prefix = &pattern[..open];
+  let suffixes = expand_brace_groups(&pattern[close + 1..]);
+  let mut result = vec![];
+  for alternative in split_brace_alternatives(&pattern[open + 1..close]) {
+    for expanded in expand_brace_groups(alternative) {
+      for suffix in &suffixes {
+        result.push(format!("{prefix}{expanded}{suffix}"));
+      }
+    }
+  }
+  result
+}
+
+/// Byte offsets of the first unescaped top-level `{` and its matching `}`
+fn find_brace_group(pattern: &str) -> Option<(usize, usize)> {
+  let mut open = None;
+  let mut depth = 0;
+  let mut chars = pattern.char_indices();
+  while let Some((idx, c)) = chars.next() {
+    match c {
+      '\\' => {
+        chars.next();
+      }
+      '{' => {
+        if depth == 0 {
+          open = Some(idx);
+        }
+        depth += 1;
+      }
+      '}' if depth > 0 => {
+        depth -= 1;
+        if depth == 0 {
+          return open.map(|open| (open, idx));
+        }
+      }
+      _ => {}
+    }
+  }
+  None
+}
+
+fn split_brace_alternatives(group: &str) -> Vec<&str> {
+  let mut alternatives = vec![];
+  let mut start = 0;
+  let mut depth = 0;
+  let mut chars = group.char_indices();
+  while let Some((idx, c)) = chars.next() {
+    match c {
+      '\\' => {
+        chars.next();
+      }
+      '{' => depth += 1,
+      '}' => depth -= 1,
+      ',' if depth == 0 => {
+        alternatives.push(&group[start..idx]);
+        start = idx + 1;
+      }
+      _ => {}
+    }
+  }
+  alternatives.push(&group[start..]);
+  alternatives
+}
+
+#[test]
+fn test_expand_braces() {
+  let expand = |pattern: &str| expand_braces(pattern.to_string());
+  assert_eq!(expand("foo.{js,ts}"), vec!["foo.js", "foo.ts"]);
+  assert_eq!(expand("{a,b}/{c,d}"), vec!["a/c", "a/d", "b/c", "b/d"]);
+  assert_eq!(expand("**/*.json"), vec!["**/*.json"]);
+  assert_eq!(expand("{a}.md"), vec!["a.md"]);
+  assert_eq!(expand("x{,.min}.js"), vec!["x.js", "x.min.js"]);
+}
+
+#[test]
+fn test_expand_braces_nested() {
+  let expand = |pattern: &str| expand_braces(pattern.to_string());
+  assert_eq!(expand("{a,b{c,d}}.ts"), vec!["a.ts", "bc.ts", "bd.ts"]);
+  assert_eq!(
+    expand("src/{lib,{test,bench}/{unit,e2e}}"),
+    vec![
+      "src/lib",
+      "src/test/unit",
+      "src/test/e2e",
+      "src/bench/unit",
+      "src/bench/e2e"
+    ]
+  );
+}
+
+#[test]
+fn test_expand_braces_escaped() {
+  let expand = |pattern: &str| expand_braces(pattern.to_string());
+  assert_eq!(expand(r"\{a,b\}"), vec![r"\{a,b\}"]);
+  assert_eq!(expand(r"{a\,b,c}"), vec![r"a\,b", "c"]);
+  assert_eq!(expand(r"{a\},b}"), vec![r"a\}", "b"]);
+  assert_eq!(expand("{a,b"), vec!["{a,b"]);
+  assert_eq!(expand("a},{b,c}"), vec!["a},b", "a},c"]);
+}
+
+/// Compiles a glob, with the spec's own flags taking precedence over `WalkOptions`.
+/// A leading `/` or `./` anchors the glob to the repository root as in `.gitignore`,
+/// unless globs are matched against basenames or a mount prefix.
+fn compile_glob_spec(repo_dir: &str, spec: &GlobSpec, options: &WalkOptions) -> Option<Glob> {
+  let glob = if options.extglob == Some(true) {
+    Cow::Owned(translate_extglob(&spec.glob)?)
+  } else {
+    Cow::Borrowed(spec.glob.as_str())
+  };
+  let glob = if options.unicode_normalize == Some(true) {
+    to_nfc(glob)
+  } else {
+    glob
+  };
+  let glob = glob.as_ref();
+  let glob = if options.match_basename != Some(true) && mount_prefix(options).is_none() {
+    // Walked paths are relative to the root already
+    let glob = strip_dot_slash(glob);
+    glob.strip_prefix('/').unwrap_or(glob)
+  } else {
+    glob
+  };
+  let glob = if options.dir_as_recursive == Some(true)
+    && is_literal_glob(glob)
+    && Path::new(repo_dir).join(glob).is_dir()
+  {
+    Cow::Owned(format!("{}/**", glob.trim_end_matches('/')))
+  } else {
+    Cow::Borrowed(glob)
+  };
+
+  let case_insensitive = spec.case_insensitive.or(options.case_insensitive);
+  globset::GlobBuilder::new(&glob)
+    .case_insensitive(case_insensitive == Some(true))
+    .literal_separator(spec.literal_separator == Some(true))
+    .build()
+    .ok()
+}
+
+/// Strips a leading `./`, which globs written by users often start with
+fn strip_dot_slash(path: &str) -> &str {
+  path.strip_prefix("./").unwrap_or(path)
+}
+
+/// Matches walked paths against globs compiled with the given `WalkOptions`
+struct GlobMatcher {
+  glob_set: globset::GlobSet,
+  match_basename: bool,
+  mount_prefix: Option<String>,
+  unicode_normalize: bool,
+}
+
+fn mount_prefix(options: &WalkOptions) -> Option<&str> {
+  options
+    .mount_prefix
+    .as_deref()
+    .map(|prefix| prefix.trim_end_matches('/'))
+    .filter(|prefix| !prefix.is_empty())
+}
+
+/// The part of `path` globs are matched against
+fn match_subject<'a>(
+  path: &'a str,
+  match_basename: bool,
+  mount_prefix: Option<&str>,
+  unicode_normalize: bool,
+) -> Cow<'a, str> {
+  let subject = if match_basename {
+    Cow::Borrowed(path.rsplit('/').next().unwrap_or(path))
+  } else if let Some(prefix) = mount_prefix {
+    Cow::Owned(format!("{}/{}", prefix, path))
+  } else {
+    Cow::Borrowed(path)
+  };
+
+  if unicode_normalize {
+    to_nfc(subject)
+  } else {
+    subject
+  }
+}
+
+/// Borrows `text` back when it's in NFC form already, which is cheap to check for ASCII
+fn to_nfc(text: Cow<'_, str>) -> Cow<'_, str> {
+  if is_nfc_quick(text.chars()) == IsNormalized::Yes {
+    text
+  } else {
+    Cow::Owned(text.nfc().collect())
+  }
+}
+
+impl GlobMatcher {
+  /// Skips invalid globs
+  fn new<S: AsRef<str>>(repo_dir: &str, globs: &[S], options: &WalkOptions) -> Option<GlobMatcher> {
+    let mut glob_builder = globset::GlobSetBuilder::new();
+    for glob in globs {
+      let Some(glob) = compile_glob(repo_dir, glob.as_ref(), options) else {
+        continue;
+      };
+
+      glob_builder.add(glob);
+    }
+
+    GlobMatcher::build(glob_builder, options)
+  }
+
+  fn single(repo_dir: &str, glob: &str, options: &WalkOptions) -> Option<GlobMatcher> {
+    let glob = compile_glob(repo_dir, glob, options)?;
+    let mut glob_builder = globset::GlobSetBuilder::new();
+    glob_builder.add(glob);
+    GlobMatcher::build(glob_builder, options)
+  }
+
+  fn build(glob_builder: globset::GlobSetBuilder, options: &WalkOptions) -> Option<GlobMatcher> {
+    let glob_set = glob_builder.build().ok()?;
+    Some(GlobMatcher {
+      glob_set,
+      match_basename: options.match_basename == Some(true),
+      mount_prefix: mount_prefix(options).map(str::to_string),
+      unicode_normalize: options.unicode_normalize == Some(true),
+    })
+  }
+
+  fn subject<'a>(&self, path: &'a str) -> Cow<'a, str> {
+    match_subject(
+      path,
+      self.match_basename,
+      self.mount_prefix.as_deref(),
+      self.unicode_normalize,
+    )
+  }
+
+  fn is_match(&self, path: &str) -> bool {
+    self.glob_set.is_match(self.subject(path).as_ref())
+  }
+
+  /// For matching one path against many matchers, the candidate
+  /// must be built from `match_subject` with the same options
+  fn is_match_candidate(&self, candidate: &globset::Candidate) -> bool {
+    self.glob_set.is_match_candidate(candidate)
+  }
+
+  fn matches(&self, path: &str) -> Vec<usize> {
+    self.glob_set.matches(self.subject(path).as_ref())
+  }
+}
+
 fn read_submodule_paths(gitmodules: &str) -> Option<Vec<String>> {
-  gix_config::File::from_str(gitmodules)
-    .ok()?
-    .sections_by_name("submodule")?
+  try_read_submodule_paths(gitmodules).ok()
+}
+
+fn try_read_submodule_paths(gitmodules: &str) -> Result<Vec<String>, String> {
+  let config = gix_config::File::from_str(gitmodules).map_err(|err| err.to_string())?;
+  let Some(sections) = config.sections_by_name("submodule") else {
+    return Ok(vec![]);
+  };
+
+  sections
     .map(|section| {
-      let value = section.value("path")?;
-      Some(value.to_string())
+      if let Some(value) = section.value("path") {
+        return Ok(normalize_submodule_path(&value.to_string()));
+      }
+
+      // Without explicit `path`, git falls back to the submodule name
+      let name = section
+        .header()
+        .subsection_name()
+        .ok_or("Submodule section has neither name nor path")?;
+      Ok(normalize_submodule_path(&name.to_string()))
+    })
+    .collect()
+}
+
+/// Drops `.` segments and redundant separators, so that
+/// e.g. `./libs//foo/` matches the walked `libs/foo`
+fn normalize_submodule_path(path: &str) -> String {
+  path
+    .split('/')
+    .filter(|segment| !segment.is_empty() && *segment != ".")
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+#[test]
+fn test_read_submodule_paths() {
+  let gitmodules = r#"
+    [submodule "foo/bar/baz"]
+            path = foo/bar/baz
+            url = https://github.com/zharinov/good-enough-parser
+  "#;
+  let paths = read_submodule_paths(gitmodules).unwrap();
+  assert_eq!(paths, vec!["foo/bar/baz".to_string()]);
+}
+
+#[test]
+fn test_read_submodule_paths_name_fallback() {
+  let gitmodules = r#"
+    [submodule "vendor/lib"]
+            url = https://github.com/zharinov/good-enough-parser
+    [submodule "other"]
+            path = deps/other
+            url = https://github.com/zharinov/good-enough-parser
+  "#;
+  let paths = read_submodule_paths(gitmodules).unwrap();
+  assert_eq!(
+    paths,
+    vec!["vendor/lib".to_string(), "deps/other".to_string()]
+  );
+}
+
+#[test]
+fn test_read_submodule_paths_normalized() {
+  let gitmodules = r#"
+    [submodule "foo"]
+            path = ./libs/foo
+    [submodule "./libs//bar/"]
+            url = https://github.com/zharinov/good-enough-parser
+  "#;
+  let paths = read_submodule_paths(gitmodules).unwrap();
+  assert_eq!(paths, vec!["libs/foo".to_string(), "libs/bar".to_string()]);
+
+  let fixture = make_fixture(&["package.json", "libs/foo/package.json"]);
+  std::fs::write(fixture.path().join(".gitmodules"), gitmodules).unwrap();
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let paths = walk_repo_glob(repo, "**/package.json".to_string(), None);
+  assert_eq!(paths, vec!["package.json"]);
+}
+
+fn get_submodule_paths(repo_path: &Path) -> Option<Vec<String>> {
+  repo_path
+    .join(".gitmodules")
+    .to_str()
+    .and_then(|gitmodules| std::fs::read_to_string(gitmodules).ok())
+    .and_then(|gitmodules| read_submodule_paths(&gitmodules))
+}
+
+/// Returns submodule paths listed in `.gitmodules` of `repoDir`, throwing with
+/// the parse error when it's malformed. Walks silently ignore such files,
+/// so this helps to find out why submodules aren't excluded.
+#[napi]
+pub fn parse_gitmodules(repo_dir: String) -> napi::Result<Vec<String>, ScanError> {
+  let gitmodules_path = Path::new(&repo_dir).join(".gitmodules");
+  let gitmodules = match std::fs::read_to_string(&gitmodules_path) {
+    Ok(gitmodules) => gitmodules,
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+    Err(err) => {
+      let path = gitmodules_path.display();
+      let reason = format!("Failed to read {}: {}", path, err);
+      return Err(scan_error(ScanError::IoError, reason));
+    }
+  };
+
+  try_read_submodule_paths(&gitmodules).map_err(|err| {
+    let path = gitmodules_path.display();
+    let reason = format!("Failed to parse {}: {}", path, err);
+    scan_error(ScanError::ConfigParse, reason)
+  })
+}
+
+/// Fails with `parseGitmodules` errors when `strictSubmodules` is set
+fn check_gitmodules(repo_dir: &str, options: &WalkOptions) -> napi::Result<(), ScanError> {
+  if options.strict_submodules != Some(true) || options.skip_submodule_check == Some(true) {
+    return Ok(());
+  }
+
+  let repo_root = if options.repo_root_relative == Some(true) {
+    get_repo_root(Path::new(repo_dir)).and_then(|(root, _)| root.to_str().map(String::from))
+  } else {
+    None
+  };
+  parse_gitmodules(repo_root.unwrap_or_else(|| repo_dir.to_string()))?;
+  Ok(())
+}
+
+#[test]
+fn test_parse_gitmodules() {
+  let fixture = make_fixture(&[]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  assert!(parse_gitmodules(repo.clone()).unwrap().is_empty());
+
+  std::fs::write(
+    fixture.path().join(".gitmodules"),
+    "[submodule \"lib\"]\n  path = vendor/lib\n",
+  )
+  .unwrap();
+  assert_eq!(parse_gitmodules(repo.clone()).unwrap(), vec!["vendor/lib"]);
+
+  std::fs::write(
+    fixture.path().join(".gitmodules"),
+    "[submodule \"lib\"\n  path = vendor/lib\n",
+  )
+  .unwrap();
+  let err = parse_gitmodules(repo).unwrap_err();
+  assert_eq!(err.status, ScanError::ConfigParse);
+  assert!(err.reason.starts_with("Failed to parse "));
+  assert!(err.reason.contains("line 1"), "{}", err.reason);
+}
+
+fn build_submodule_glob(paths: Vec<String>) -> Option<globset::GlobSet> {
+  let mut glob_builder = globset::GlobSetBuilder::new();
+  for path in paths {
+    if let Ok(glob) = Glob::new(&path) {
+      glob_builder.add(glob);
+    }
+  }
+  glob_builder.build().ok()
+}
+
+/// Returns the git repository containing `repo_path`, its root
+/// and the `/`-separated location of `repo_path` inside of it.
+fn discover_repo(repo_path: &Path) -> Option<(gix::Repository, PathBuf, String)> {
+  let repo = gix::discover(repo_path).ok()?;
+  let root = repo.work_dir()?.canonicalize().ok()?;
+  let repo_path = repo_path.canonicalize().ok()?;
+  let prefix = strip_path_prefix(&repo_path, &root)?;
+  let prefix = to_slash_path(&prefix)?.into_owned();
+  Some((repo, root, prefix))
+}
+
+fn get_repo_root(repo_path: &Path) -> Option<(PathBuf, String)> {
+  let (_, root, prefix) = discover_repo(repo_path)?;
+  Some((root, prefix))
+}
+
+#[test]
+fn test_get_repo_root() {
+  let fixture = make_fixture(&["packages/a/package.json"]);
+  assert!(get_repo_root(fixture.path()).is_none());
+
+  gix::init(fixture.path()).unwrap();
+  let (root, prefix) = get_repo_root(&fixture.path().join("packages/a")).unwrap();
+  assert_eq!(root, fixture.path().canonicalize().unwrap());
+  assert_eq!(prefix, "packages/a");
+}
+
+/// Walks up from `startDir` to the closest directory containing `.git`, which is
+/// either a directory or a file as in submodules and worktrees. Returns `null`
+/// when the filesystem root is reached without finding one.
+#[napi]
+pub fn find_repo_root(start_dir: String) -> Option<String> {
+  let start_path = Path::new(&start_dir).canonicalize().ok()?;
+  let root = start_path
+    .ancestors()
+    .find(|dir| dir.join(".git").exists())?;
+  let root = root.to_str()?;
+  Some(simplify_verbatim_path(root).into_owned())
+}
+
+#[test]
+fn test_find_repo_root() {
+  let manifest_dir = env!("CARGO_MANIFEST_DIR");
+  let start_dir = Path::new(manifest_dir).join("src");
+  let root = find_repo_root(start_dir.to_str().unwrap().to_string()).unwrap();
+  assert_eq!(
+    Path::new(&root),
+    Path::new(manifest_dir).canonicalize().unwrap()
+  );
+}
+
+#[test]
+fn test_find_repo_root_git_file() {
+  let fixture = make_fixture(&["vendor/dep/.git", "vendor/dep/src/lib.rs"]);
+  let start_dir = fixture.path().join("vendor/dep/src");
+  let root = find_repo_root(start_dir.to_str().unwrap().to_string()).unwrap();
+  assert_eq!(
+    Path::new(&root),
+    fixture.path().join("vendor/dep").canonicalize().unwrap()
+  );
+
+  let missing = fixture.path().join("missing");
+  assert!(find_repo_root(missing.to_str().unwrap().to_string()).is_none());
+}
+
+/// Working tree of the main repository, if `repo_path` is within a linked worktree
+fn main_worktree_dir(repo_path: &Path) -> Option<PathBuf> {
+  let repo = gix::discover(repo_path).ok()?;
+  if repo.git_dir() == repo.common_dir() {
+    return None;
+  }
+
+  let main_repo = repo.main_repo().ok()?;
+  Some(main_repo.work_dir()?.to_path_buf())
+}
+
+#[test]
+fn test_walk_repo_glob_linked_worktree() {
+  let fixture = make_fixture(&["a.json", "vendor/dep/b.json"]);
+  std::fs::write(
+    fixture.path().join(".gitmodules"),
+    "[submodule \"dep\"]\n  path = vendor/dep\n",
+  )
+  .unwrap();
+  git(fixture.path(), &["init", "-q"]);
+  git(fixture.path(), &["add", "."]);
+  git(fixture.path(), &["commit", "-q", "-m", "init"]);
+
+  let worktrees = tempfile::tempdir().unwrap();
+  let worktree = worktrees.path().join("wt");
+  git(
+    fixture.path(),
+    &["worktree", "add", "-q", worktree.to_str().unwrap()],
+  );
+  std::fs::remove_file(worktree.join(".gitmodules")).unwrap();
+
+  let repo = worktree.to_str().unwrap().to_string();
+  let paths = walk_repo_glob(repo.clone(), "**/*.json".to_string(), None);
+  assert_eq!(paths, vec!["a.json", "vendor/dep/b.json"]);
+
+  let options = Some(WalkOptions {
+    linked_worktree: Some(true),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo, "**/*.json".to_string(), options);
+  assert_eq!(paths, vec!["a.json"]);
+}
+
+/// Git index of the repository containing `repo_dir`, keyed
+/// by the same paths `walk_repo` reports for these options.
+struct RepoIndex {
+  repo: gix::Repository,
+  index: gix::index::File,
+  prefix: String,
+}
+
+impl RepoIndex {
+  fn open(repo_dir: &str, options: &WalkOptions) -> Option<RepoIndex> {
+    let (repo, _, prefix) = discover_repo(Path::new(repo_dir))?;
+    let index = repo.open_index().ok()?;
+    let prefix = if options.repo_root_relative == Some(true) {
+      String::new()
+    } else {
+      prefix
+    };
+    // Walked paths have `relativeTo` stripped, while the index has it
+    let prefix = match relative_to_dir(options) {
+      Some(dir) => join_slash_path(&prefix, dir).into_owned(),
+      None => prefix,
+    };
+    Some(RepoIndex {
+      repo,
+      index,
+      prefix,
+    })
+  }
+
+  fn entry(&self, path: &str) -> Option<&gix::index::Entry> {
+    let path = join_slash_path(&self.prefix, path);
+    self.index.entry_by_path(path.as_ref().into())
+  }
+
+  /// Blob ids of the `HEAD` commit keyed by the same paths as `entry`,
+  /// empty for repositories without commits
+  fn head_blobs(&self) -> HashMap<String, gix::ObjectId> {
+    let Some(tree) = self
+      .repo
+      .head_commit()
+      .ok()
+      .and_then(|commit| commit.tree().ok())
+    else {
+      return HashMap::new();
+    };
+
+    let Ok(files) = tree.traverse().breadthfirst.files() else {
+      return HashMap::new();
+    };
+
+    files
+      .into_iter()
+      .filter(|file| file.mode.is_blob())
+      .filter_map(|file| {
+        let path = file.filepath.to_string();
+        let path = if self.prefix.is_empty() {
+          path
+        } else {
+          path.strip_prefix(&format!("{}/", self.prefix))?.to_string()
+        };
+        Some((path, file.oid))
+      })
+      .collect()
+  }
+
+  /// Whether the working tree file differs from its index entry. Like `git status`,
+  /// files are only read and hashed when their size matches the index entry, but
+  /// their stat data doesn't or was recorded too close to the last change to be trusted.
+  fn is_modified(&self, entry: &gix::index::Entry, file_path: &Path) -> bool {
+    let Ok(metadata) = std::fs::symlink_metadata(file_path) else {
+      return true;
+    };
+
+    if metadata.len() as u32 != entry.stat.size {
+      return true;
+    }
+
+    if self.is_stat_clean(entry, &metadata) {
+      return false;
+    }
+
+    let Ok(data) = std::fs::read(file_path) else {
+      return true;
+    };
+
+    let oid = gix::objs::compute_hash(self.repo.object_hash(), gix::objs::Kind::Blob, &data);
+    oid != entry.id
+  }
+
+  /// Whether the file's stat data matches its index entry, which was written after
+  /// the file's last modification, so that no change could go unnoticed (racy git)
+  fn is_stat_clean(&self, entry: &gix::index::Entry, metadata: &std::fs::Metadata) -> bool {
+    let options = gix::index::entry::stat::Options {
+      use_nsec: true,
+      ..Default::default()
+    };
+    index_stat(metadata).is_some_and(|stat| {
+      entry.stat.matches(&stat, options) && !entry.stat.is_racy(self.index.timestamp(), options)
+    })
+  }
+}
+
+/// Stat data of a file in the form git records it in the index. Unlike
+/// `Stat::from_fs`, this uses the status change time on Unix, as git does.
+#[cfg(unix)]
+fn index_stat(metadata: &std::fs::Metadata) -> Option<gix::index::entry::Stat> {
+  use gix::index::entry::stat::Time;
+  use std::os::unix::fs::MetadataExt;
+
+  let time = |secs: i64, nsecs: i64| Time {
+    secs: secs as u32,
+    nsecs: nsecs as u32,
+  };
+  Some(gix::index::entry::Stat {
+    mtime: time(metadata.mtime(), metadata.mtime_nsec()),
+    ctime: time(metadata.ctime(), metadata.ctime_nsec()),
+    dev: metadata.dev() as u32,
+    ino: metadata.ino() as u32,
+    uid: metadata.uid(),
+    gid: metadata.gid(),
+    size: metadata.len() as u32,
+  })
+}
+
+#[cfg(not(unix))]
+fn index_stat(metadata: &std::fs::Metadata) -> Option<gix::index::entry::Stat> {
+  gix::index::entry::Stat::from_fs(metadata).ok()
+}
+
+#[test]
+fn test_repo_index_stat_clean() {
+  let fixture = make_fixture(&[]);
+  let file_path = fixture.path().join("a.txt");
+  let an_hour_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+  let set_old_mtime = || {
+    let file = std::fs::File::options()
+      .write(true)
+      .open(&file_path)
+      .unwrap();
+    file.set_modified(an_hour_ago).unwrap();
+  };
+  std::fs::write(&file_path, "a").unwrap();
+  set_old_mtime();
+  git(fixture.path(), &["init", "-q"]);
+  git(fixture.path(), &["add", "a.txt"]);
+
+  let repo = fixture.path().to_str().unwrap();
+  let index = RepoIndex::open(repo, &WalkOptions::default()).unwrap();
+  let entry = index.entry("a.txt").unwrap();
+  let metadata = std::fs::symlink_metadata(&file_path).unwrap();
+  // Unchanged files aren't read
+  assert!(index.is_stat_clean(entry, &metadata));
+  assert!(!index.is_modified(entry, &file_path));
+
+  // Changes keeping the size and modification time still show in the status change time
+  std::thread::sleep(std::time::Duration::from_millis(50));
+  std::fs::write(&file_path, "b").unwrap();
+  set_old_mtime();
+  let metadata = std::fs::symlink_metadata(&file_path).unwrap();
+  assert!(!index.is_stat_clean(entry, &metadata));
+  assert!(index.is_modified(entry, &file_path));
+}
+
+#[test]
+fn test_repo_index_relative_to() {
+  let fixture = make_fixture(&["packages/a/index.js", "packages/b.js"]);
+  std::fs::write(fixture.path().join("packages/a/index.js"), "a").unwrap();
+  git(fixture.path(), &["init", "-q"]);
+  git(fixture.path(), &["add", "."]);
+  git(fixture.path(), &["commit", "-q", "-m", "init"]);
+
+  let repo = fixture.path().join("packages");
+  let options = WalkOptions {
+    relative_to: Some("a".to_string()),
+    ..Default::default()
+  };
+  let index = RepoIndex::open(repo.to_str().unwrap(), &options).unwrap();
+  assert!(index.entry("index.js").is_some());
+  assert!(index.entry("b.js").is_none());
+  let head_blobs = index.head_blobs();
+  assert_eq!(head_blobs.keys().collect::<Vec<_>>(), vec!["index.js"]);
+}
+
+#[cfg(test)]
+fn git(repo: &Path, args: &[&str]) {
+  let status = std::process::Command::new("git")
+    .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+    .args(args)
+    .current_dir(repo)
+    .status()
+    .unwrap();
+  assert!(status.success());
+}
+
+fn walk_repo<F, Res>(repo_dir: &str, options: &WalkOptions, f: F) -> Vec<Res>
+where
+  F: FnMut(&str, &ignore::DirEntry) -> Option<Res>,
+{
+  walk_repo_scoped(repo_dir, None, options, f)
+}
+
+/// Checks whether `path` is inside of one of `subdirs`,
+/// or is a directory leading to one of them
+fn is_within_subdirs(subdirs: &[String], path: &str, is_dir: bool) -> bool {
+  let is_below = |path: &str, dir: &str| {
+    dir.is_empty()
+      || path == dir
+      || path
+        .strip_prefix(dir)
+        .is_some_and(|rest| rest.starts_with('/'))
+  };
+
+  subdirs
+    .iter()
+    .any(|subdir| is_below(path, subdir) || (is_dir && is_below(subdir, path)))
+}
+
+#[test]
+fn test_is_within_subdirs() {
+  let subdirs = vec!["packages/a".to_string()];
+  assert!(is_within_subdirs(&subdirs, "packages/a/index.js", false));
+  assert!(is_within_subdirs(&subdirs, "packages/a", true));
+  assert!(is_within_subdirs(&subdirs, "packages", true));
+  assert!(!is_within_subdirs(&subdirs, "packages", false));
+  assert!(!is_within_subdirs(&subdirs, "packages/ab", true));
+  assert!(!is_within_subdirs(&subdirs, "README.md", false));
+}
+
+/// Parts of the walk setup depending on the repository only,
+/// which can be reused across walks
+#[derive(Clone)]
+struct RepoLayout {
+  /// Prefix of reported paths, non-empty with `repoRootRelative`
+  root_prefix: String,
+  submodule_glob: Option<globset::GlobSet>,
+  /// Rules of `.scanignore`, if enabled and present
+  scanignore: Option<ignore::gitignore::Gitignore>,
+}
+
+impl RepoLayout {
+  fn load(repo_dir: &str, options: &WalkOptions) -> RepoLayout {
+    let repo_path = Path::new(repo_dir);
+
+    let repo_root = if options.repo_root_relative == Some(true) {
+      get_repo_root(repo_path)
+    } else {
+      None
+    };
+
+    let (submodules_path, root_prefix) = match &repo_root {
+      Some((root, prefix)) => (root.as_path(), prefix.clone()),
+      None => (repo_path, String::new()),
+    };
+
+    let submodule_paths = if options.skip_submodule_check == Some(true) {
+      None
+    } else if options.linked_worktree == Some(true) {
+      get_submodule_paths(submodules_path).or_else(|| {
+        let main_dir = main_worktree_dir(submodules_path)?;
+        get_submodule_paths(&main_dir)
+      })
+    } else {
+      get_submodule_paths(submodules_path)
+    };
+
+    let scanignore = if options.use_scanignore == Some(true) {
+      load_scanignore(repo_path)
+    } else {
+      None
+    };
+
+    RepoLayout {
+      root_prefix,
+      submodule_glob: submodule_paths.and_then(build_submodule_glob),
+      scanignore,
+    }
+  }
+}
+
+/// Invalid lines are skipped like git does, the rest still apply
+fn load_scanignore(repo_path: &Path) -> Option<ignore::gitignore::Gitignore> {
+  let path = repo_path.join(".scanignore");
+  if !path.is_file() {
+    return None;
+  }
+
+  let mut builder = ignore::gitignore::GitignoreBuilder::new(repo_path);
+  builder.add(path);
+  builder.build().ok()
+}
+
+/// Configures the walk shared by all functions: ignore files, pruned directories
+/// and submodules
+fn build_walker(
+  repo_dir: &str,
+  subdirs: Option<Vec<String>>,
+  options: &WalkOptions,
+  layout: &RepoLayout,
+) -> ignore::WalkBuilder {
+  let repo_path = Path::new(repo_dir);
+  let submodule_glob = layout.submodule_glob.clone();
+
+  let mut walk_builder = ignore::WalkBuilder::new(repo_path);
+  walk_builder.follow_links(false);
+  walk_builder.parents(false);
+  walk_builder.hidden(false);
+  walk_builder.git_exclude(false);
+  walk_builder.ignore(options.use_ignore_files != Some(false));
+  if options.use_rgignore == Some(true) {
+    walk_builder.add_custom_ignore_filename(".rgignore");
+  }
+
+  let prune_dirs: HashSet<String> = options.prune_dirs.iter().flatten().cloned().collect();
+
+  let include_symlinks = options.include_symlinks == Some(true);
+
+  let stop_at_marker = options.stop_at_marker.clone();
+
+  let prefix = repo_dir.to_string();
+  let filter_root_prefix = layout.root_prefix.clone();
+  walk_builder.filter_entry(move |entry| {
+    let Some(file_type) = entry.file_type() else {
+      return false;
+    };
+
+    if let Some(subdirs) = &subdirs {
+      let Some(path) = strip_path_prefix(entry.path(), Path::new(&prefix)) else {
+        return false;
+      };
+
+      let Some(path) = to_slash_path(&path) else {
+        return false;
+      };
+
+      if !path.is_empty() && !is_within_subdirs(subdirs, &path, file_type.is_dir()) {
+        return false;
+      }
+    }
+
+    if file_type.is_file() {
+      return true;
+    }
+
+    if file_type.is_symlink() {
+      return include_symlinks;
+    }
+
+    let Some(path) = strip_path_prefix(entry.path(), Path::new(&prefix)) else {
+      return false;
+    };
+
+    let Some(dir_name) = path.file_name() else {
+      return false;
+    };
+
+    let Some(dir_name) = dir_name.to_str() else {
+      return false;
+    };
+
+    if dir_name == ".git" || prune_dirs.contains(dir_name) {
+      return false;
+    }
+
+    if let Some(glob) = &submodule_glob {
+      let Some(path) = to_slash_path(&path) else {
+        return false;
+      };
+
+      if glob.is_match(join_slash_path(&filter_root_prefix, &path).as_ref()) {
+        return false;
+      }
+    }
+
+    if let Some(marker) = &stop_at_marker {
+      let parent = entry.path().parent();
+      if parent.is_some_and(|parent| parent.join(marker).is_file()) {
+        return false;
+      }
+    }
+
+    true
+  });
+
+  if options.sort != Some(false) {
+    walk_builder.sort_by_file_path(|a, b| files_first_cmp((a, a.is_dir()), (b, b.is_dir())));
+  }
+
+  walk_builder
+}
+
+/// Walk order of sibling entries: files before directories, each sorted by name
+fn files_first_cmp<T: Ord>(
+  (a, a_is_dir): (T, bool),
+  (b, b_is_dir): (T, bool),
+) -> std::cmp::Ordering {
+  a_is_dir.cmp(&b_is_dir).then_with(|| a.cmp(&b))
+}
+
+/// Orders slash paths the way a sorted walk yields them
+fn walk_order_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+  let mut a_segments = a.split('/').peekable();
+  let mut b_segments = b.split('/').peekable();
+  loop {
+    match (a_segments.next(), b_segments.next()) {
+      (Some(a_name), Some(b_name)) if a_name == b_name => continue,
+      (Some(a_name), Some(b_name)) => {
+        let a_is_dir = a_segments.peek().is_some();
+        let b_is_dir = b_segments.peek().is_some();
+        return files_first_cmp((a_name, a_is_dir), (b_name, b_is_dir));
+      }
+      (a_name, b_name) => return a_name.is_some().cmp(&b_name.is_some()),
+    }
+  }
+}
+
+#[test]
+fn test_build_walker_prunes_ignored_dirs() {
+  let mut files = vec!["src/main.rs".to_string(), "build/.gitignore".to_string()];
+  files.extend((0..100).map(|idx| format!("build/out/{}.o", idx)));
+  let files: Vec<&str> = files.iter().map(String::as_str).collect();
+  let fixture = make_fixture(&files);
+  gix::init(fixture.path()).unwrap();
+  std::fs::write(fixture.path().join(".gitignore"), "build/\n").unwrap();
+
+  // A broken ignore file attaches an error to its directory once it's visited
+  std::fs::write(fixture.path().join("build/.gitignore"), "[z-a]\n").unwrap();
+  let has_error = |entry: Result<ignore::DirEntry, ignore::Error>| entry.unwrap().error().is_some();
+
+  let repo = fixture.path().to_str().unwrap();
+  let options = WalkOptions::default();
+  let walk_builder = build_walker(repo, None, &options, &RepoLayout::load(repo, &options));
+  let visited: Vec<_> = walk_builder.build().collect();
+
+  // Root, `.gitignore`, `src` and `src/main.rs`
+  assert_eq!(visited.len(), 4);
+  assert!(!visited.into_iter().any(has_error));
+
+  std::fs::write(fixture.path().join(".gitignore"), "").unwrap();
+  let options = WalkOptions::default();
+  let walk_builder = build_walker(repo, None, &options, &RepoLayout::load(repo, &options));
+  assert!(walk_builder.build().any(has_error));
+}
+
+/// Same as `walk_repo`, but when `subdirs` are given, only descends into them.
+/// Ignore files and `.gitmodules` are still read starting from `repo_dir`.
+fn walk_repo_scoped<F, Res>(
+  repo_dir: &str,
+  subdirs: Option<Vec<String>>,
+  options: &WalkOptions,
+  f: F,
+) -> Vec<Res>
+where
+  F: FnMut(&str, &ignore::DirEntry) -> Option<Res>,
+{
+  let layout = RepoLayout::load(repo_dir, options);
+  walk_repo_with_layout(repo_dir, subdirs, options, &layout, f)
+}
+
+fn walk_repo_with_layout<F, Res>(
+  repo_dir: &str,
+  subdirs: Option<Vec<String>>,
+  options: &WalkOptions,
+  layout: &RepoLayout,
+  f: F,
+) -> Vec<Res>
+where
+  F: FnMut(&str, &ignore::DirEntry) -> Option<Res>,
+{
+  walk_repo_iter(repo_dir, subdirs, options, layout, f).collect()
+}
+
+/// Lazy version of `walk_repo_with_layout`, walking only as far as the result is consumed
+fn walk_repo_iter<'a, F, Res>(
+  repo_dir: &'a str,
+  subdirs: Option<Vec<String>>,
+  options: &'a WalkOptions,
+  layout: &'a RepoLayout,
+  mut f: F,
+) -> impl Iterator<Item = Res> + 'a
+where
+  F: FnMut(&str, &ignore::DirEntry) -> Option<Res> + 'a,
+  Res: 'a,
+{
+  let repo_path = Path::new(repo_dir);
+
+  // When `repo_dir` points to a file, it's the only entry walked
+  // and it's reported relative to its parent directory
+  let base_path = if repo_path.is_file() {
+    repo_path.parent().unwrap_or(repo_path)
+  } else {
+    repo_path
+  };
+
+  let walk_builder = build_walker(repo_dir, subdirs, options, layout);
+  let include_symlinks = options.include_symlinks == Some(true);
+  let relative_to = relative_to_dir(options);
+  let case_insensitive = options.case_insensitive == Some(true);
+  let skip_empty = options.skip_empty == Some(true);
+  let excluded_suffixes: Vec<String> = options
+    .exclude_extensions
+    .iter()
+    .flatten()
+    .map(|ext| {
+      let suffix = format!(".{}", ext.trim_start_matches('.'));
+      if case_insensitive {
+        suffix.to_lowercase()
+      } else {
+        suffix
+      }
+    })
+    .collect();
+
+  let excluded_paths: HashSet<String> = options.exclude_paths.iter().flatten().cloned().collect();
+
+  let mut throttle = options.max_entries_per_second.map(Throttle::new);
+
+  walk_builder.build().filter_map(move |entry| {
+    if let Some(throttle) = &mut throttle {
+      throttle.tick();
+    }
+
+    let Ok(entry) = entry else {
+      return None;
+    };
+
+    let file_type = entry.file_type()?;
+    let is_symlink = include_symlinks && file_type.is_symlink();
+    if !file_type.is_file() && !is_symlink {
+      return None;
+    }
+
+    if skip_empty && file_type.is_file() && entry.metadata().is_ok_and(|meta| meta.len() == 0) {
+      return None;
+    }
+
+    if !excluded_suffixes.is_empty() {
+      let name = entry.file_name().to_str()?;
+      let name = if case_insensitive {
+        Cow::Owned(name.to_lowercase())
+      } else {
+        Cow::Borrowed(name)
+      };
+
+      // Names consisting of the suffix only are dotfiles, e.g. `.lock`
+      let has_excluded_extension = excluded_suffixes
+        .iter()
+        .any(|suffix| name.len() > suffix.len() && name.ends_with(suffix.as_str()));
+      if has_excluded_extension {
+        return None;
+      }
+    }
+
+    let path = strip_path_prefix(entry.path(), base_path)?;
+    let path = to_slash_path(&path)?;
+    if path.is_empty() {
+      return None;
+    }
+
+    if let Some(scanignore) = &layout.scanignore {
+      let is_ignored = scanignore
+        .matched_path_or_any_parents(path.as_ref(), false)
+        .is_ignore();
+      if is_ignored {
+        return None;
+      }
+    }
+
+    let full_path = join_slash_path(&layout.root_prefix, &path);
+    let path = match relative_to {
+      Some(dir) => full_path.strip_prefix(dir)?.strip_prefix('/')?,
+      None => &full_path,
+    };
+
+    if excluded_paths.contains(path) {
+      return None;
+    }
+
+    f(path, &entry)
+  })
+}
+
+/// Directory walked paths are reported relative to, stripped by `walk_repo`
+fn relative_to_dir(options: &WalkOptions) -> Option<&str> {
+  options
+    .relative_to
+    .as_deref()
+    .map(|dir| dir.trim_matches('/'))
+    .filter(|dir| !dir.is_empty())
+}
+
+/// Paces a loop to at most `rate` iterations per second
+struct Throttle {
+  rate: u32,
+  start: std::time::Instant,
+  count: u32,
+}
+
+impl Throttle {
+  fn new(rate: u32) -> Throttle {
+    Throttle {
+      rate: rate.max(1),
+      start: std::time::Instant::now(),
+      count: 0,
+    }
+  }
+
+  fn tick(&mut self) {
+    let due = std::time::Duration::from_secs_f64(self.count as f64 / self.rate as f64);
+    self.count += 1;
+    if let Some(ahead) = due.checked_sub(self.start.elapsed()) {
+      std::thread::sleep(ahead);
+    }
+  }
+}
+
+/// Applies `lowercaseOutput` and `nativeSeparators` to a returned path
+fn output_path(path: String, options: &WalkOptions) -> String {
+  let path = if options.lowercase_output == Some(true) {
+    path.to_lowercase()
+  } else {
+    path
+  };
+
+  if options.native_separators == Some(true) && std::path::MAIN_SEPARATOR != '/' {
+    path.replace('/', std::path::MAIN_SEPARATOR_STR)
+  } else {
+    path
+  }
+}
+
+/// Applies output options to the collected paths
+fn finish_paths(paths: Vec<String>, options: &WalkOptions) -> Vec<String> {
+  let paths = paths.into_iter().map(|path| output_path(path, options));
+  let mut paths: Vec<String> = if options.unique_sorted == Some(true) {
+    let paths: BTreeSet<String> = paths.collect();
+    paths.into_iter().collect()
+  } else {
+    paths.collect()
+  };
+
+  if options.natural_sort == Some(true) {
+    paths.sort_by(|a, b| natural_cmp(a, b));
+  }
+
+  paths
+}
+
+/// Compares strings with runs of ASCII digits compared by their numeric value.
+/// Equal numbers with different leading zeros are ordered lexically.
+fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+  let (mut a, mut b) = (a.as_bytes(), b.as_bytes());
+  loop {
+    match (a.first(), b.first()) {
+      (None, None) => return std::cmp::Ordering::Equal,
+      (None, Some(_)) => return std::cmp::Ordering::Less,
+      (Some(_), None) => return std::cmp::Ordering::Greater,
+      (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+        let a_len = a.iter().take_while(|c| c.is_ascii_digit()).count();
+        let b_len = b.iter().take_while(|c| c.is_ascii_digit()).count();
+        let (a_digits, a_rest) = a.split_at(a_len);
+        let (b_digits, b_rest) = b.split_at(b_len);
+
+        let a_num = &a_digits[a_digits.iter().take_while(|c| **c == b'0').count()..];
+        let b_num = &b_digits[b_digits.iter().take_while(|c| **c == b'0').count()..];
+        let ordering = a_num
+          .len()
+          .cmp(&b_num.len())
+          .then_with(|| a_num.cmp(b_num))
+          .then_with(|| a_digits.cmp(b_digits));
+        if ordering.is_ne() {
+          return ordering;
+        }
+
+        (a, b) = (a_rest, b_rest);
+      }
+      (Some(x), Some(y)) => {
+        if x != y {
+          return x.cmp(y);
+        }
+
+        (a, b) = (&a[1..], &b[1..]);
+      }
+    }
+  }
+}
+
+#[test]
+fn test_natural_cmp() {
+  let mut names = vec![
+    "file10.json",
+    "file2.json",
+    "file1.json",
+    "file02.json",
+    "file.json",
+    "a10b2",
+    "a10b10",
+    "a9",
+  ];
+  names.sort_by(|a, b| natural_cmp(a, b));
+  assert_eq!(
+    names,
+    vec![
+      "a9",
+      "a10b2",
+      "a10b10",
+      "file.json",
+      "file1.json",
+      "file02.json",
+      "file2.json",
+      "file10.json"
+    ]
+  );
+}
+
+#[test]
+fn test_walk_repo_glob_natural_sort() {
+  let fixture = make_fixture(&[
+    "file10.json",
+    "file2.json",
+    "file1.json",
+    "dir10/a.json",
+    "dir9/a.json",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let options = Some(WalkOptions {
+    natural_sort: Some(true),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo, "**/*.json".to_string(), options);
+  assert_eq!(
+    paths,
+    vec![
+      "dir9/a.json",
+      "dir10/a.json",
+      "file1.json",
+      "file2.json",
+      "file10.json"
+    ]
+  );
+}
+
+fn walk_repo_paths<F>(repo_dir: &str, options: &WalkOptions, f: F) -> Vec<String>
+where
+  F: FnMut(&str, &ignore::DirEntry) -> Option<String>,
+{
+  let paths = walk_repo(repo_dir, options, f);
+  finish_paths(paths, options)
+}
+
+#[napi]
+pub fn glob_to_regex(glob: String) -> Option<String> {
+  let Ok(glob) = Glob::new(&glob) else {
+    return None;
+  };
+
+  Some(glob.regex().to_string())
+}
+
+/// Filters virtual paths, e.g. `a::b::c` module paths, by `glob` written with
+/// the same `separator` (defaults to `/`). Unlike in the walk functions, `*` doesn't
+/// match the separator, while `**` matches any number of segments.
+///
+/// Matching is done by substituting `separator` with `/`, so segments must not contain
+/// `/` themselves, and the separator can't be escaped. Every occurrence of `separator`
+/// in `glob` is a segment boundary, e.g. with `.`, `*.java` matches `Foo.java` as two
+/// segments, but not `com.Foo.java`. Separators containing any of the glob
+/// metacharacters `*?[]{}!\` would change the meaning of `glob`, so they throw
+/// `InvalidGlob`. Paths are returned unchanged.
+#[napi]
+pub fn match_paths(
+  paths: Vec<String>,
+  glob: String,
+  separator: Option<String>,
+  options: Option<WalkOptions>,
+) -> napi::Result<Vec<String>, ScanError> {
+  // Paths are returned as given, not converted to native ones
+  let options = WalkOptions {
+    native_separators: None,
+    ..options.unwrap_or_default()
+  };
+  let separator = separator.filter(|separator| !separator.is_empty());
+  if let Some(separator) = separator
+    .as_ref()
+    .filter(|separator| separator.contains(['*', '?', '[', ']', '{', '}', '!', '\\']))
+  {
+    let reason = format!("Separator contains glob metacharacters: {}", separator);
+    return Err(scan_error(ScanError::InvalidGlob, reason));
+  }
+
+  let normalize = |path: &str| match &separator {
+    Some(separator) => path.replace(separator.as_str(), "/"),
+    None => path.to_string(),
+  };
+
+  let Ok(glob) = globset::GlobBuilder::new(&normalize(&glob))
+    .case_insensitive(options.case_insensitive == Some(true))
+    .literal_separator(true)
+    .build()
+  else {
+    return Ok(vec![]);
+  };
+
+  let mut glob_builder = globset::GlobSetBuilder::new();
+  glob_builder.add(glob);
+  let Some(matcher) = GlobMatcher::build(glob_builder, &options) else {
+    return Ok(vec![]);
+  };
+
+  let paths = paths
+    .into_iter()
+    .filter(|path| matcher.is_match(&normalize(path)))
+    .collect();
+  Ok(finish_paths(paths, &options))
+}
+
+#[test]
+fn test_match_paths() {
+  let paths: Vec<String> = vec![
+    "std::io::Read",
+    "std::io::prelude::Read",
+    "std::fmt::Write",
+    "core::Read",
+  ]
+  .into_iter()
+  .map(String::from)
+  .collect();
+
+  let sep = Some("::".to_string());
+  let matched = match_paths(paths.clone(), "std::*::Read".to_string(), sep.clone(), None).unwrap();
+  assert_eq!(matched, vec!["std::io::Read"]);
+
+  let matched = match_paths(paths.clone(), "**::Read".to_string(), sep.clone(), None).unwrap();
+  assert_eq!(
+    matched,
+    vec!["std::io::Read", "std::io::prelude::Read", "core::Read"]
+  );
+
+  let matched = match_paths(paths.clone(), "std::**".to_string(), sep, None).unwrap();
+  assert_eq!(matched.len(), 3);
+
+  let matched = match_paths(paths, "std/*/Read".to_string(), None, None).unwrap();
+  assert!(matched.is_empty());
+}
+
+#[test]
+fn test_match_paths_dotted_separator() {
+  let paths: Vec<String> = vec!["com.example.Foo", "com.example.util.Foo", "Foo.java"]
+    .into_iter()
+    .map(String::from)
+    .collect();
+
+  let sep = Some(".".to_string());
+  let matched = match_paths(paths.clone(), "com.*.Foo".to_string(), sep.clone(), None).unwrap();
+  assert_eq!(matched, vec!["com.example.Foo"]);
+
+  // The separator splits the glob as well
+  let matched = match_paths(paths.clone(), "*.java".to_string(), sep, None).unwrap();
+  assert_eq!(matched, vec!["Foo.java"]);
+
+  for sep in ["*", "{", "\\"] {
+    let err = match_paths(paths.clone(), "**".to_string(), Some(sep.to_string()), None);
+    assert_eq!(err.unwrap_err().status, ScanError::InvalidGlob);
+  }
+}
+
+/// Filters `paths`, e.g. from `git ls-tree` output, by `globs` the same way
+/// `walkRepoGlobs` matches walked files, without accessing the file system.
+/// Paths are expected to be `/`-separated, and `dirAsRecursive` is ignored.
+/// A leading `./` is stripped from paths, as walked paths never have one.
+#[napi]
+pub fn match_paths_against_globs(
+  paths: Vec<String>,
+  globs: Vec<String>,
+  options: Option<WalkOptions>,
+) -> Vec<String> {
+  let options = WalkOptions {
+    dir_as_recursive: None,
+    ..options.unwrap_or_default()
+  };
+  let Some(matcher) = GlobMatcher::new("", &globs, &options) else {
+    return vec![];
+  };
+
+  let paths = paths
+    .into_iter()
+    .map(|path| match path.strip_prefix("./") {
+      Some(stripped) => stripped.to_string(),
+      None => path,
+    })
+    .filter(|path| matcher.is_match(path))
+    .collect();
+  finish_paths(paths, &options)
+}
+
+#[test]
+fn test_match_paths_against_globs() {
+  let fixture = make_fixture(&[
+    "package.json",
+    "src/index.ts",
+    "src/util/index.ts",
+    "docs/README.md",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let globs = vec!["**/*.ts".to_string(), "*.json".to_string()];
+
+  let all = walk_repo_glob(repo.clone(), "**".to_string(), None);
+  let matched = match_paths_against_globs(all, globs.clone(), None);
+  assert_eq!(matched, walk_repo_globs(repo, globs, None));
+  assert_eq!(
+    matched,
+    vec!["package.json", "src/index.ts", "src/util/index.ts"]
+  );
+}
+
+/// Returns `paths` naming existing files under `repoDir`, checking each one directly
+/// instead of walking. Like in walks, files inside submodules and `.git` are skipped,
+/// but ignore files aren't taken into account. Absolute paths and paths with `..`
+/// segments are dropped, as they could point outside of `repoDir`.
+#[napi]
+pub fn filter_existing_paths(repo_dir: String, paths: Vec<String>) -> Vec<String> {
+  let repo_path = Path::new(&repo_dir);
+  let submodule_glob = get_submodule_paths(repo_path).and_then(build_submodule_glob);
+
+  paths
+    .into_iter()
+    .filter(|path| {
+      let slash_path = path.replace('\\', "/");
+      let is_within_repo = Path::new(&slash_path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+      if !is_within_repo {
+        return false;
+      }
+
+      let mut dirs = slash_path
+        .match_indices('/')
+        .map(|(idx, _)| &slash_path[..idx]);
+      let is_excluded = dirs.any(|dir| {
+        dir == ".git"
+          || dir.ends_with("/.git")
+          || submodule_glob
+            .as_ref()
+            .is_some_and(|glob| glob.is_match(dir))
+      });
+
+      !is_excluded && repo_path.join(&slash_path).is_file()
+    })
+    .collect()
+}
+
+#[test]
+fn test_filter_existing_paths() {
+  let fixture = make_fixture(&["package.json", "src/index.js", "vendor/dep/package.json"]);
+  std::fs::write(
+    fixture.path().join(".gitmodules"),
+    "[submodule \"dep\"]\n  path = vendor/dep\n",
+  )
+  .unwrap();
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let paths = vec![
+    "package.json",
+    "missing.json",
+    "src",
+    "src/index.js",
+    "vendor/dep/package.json",
+  ];
+  let paths = filter_existing_paths(repo.clone(), paths.into_iter().map(String::from).collect());
+  assert_eq!(paths, vec!["package.json", "src/index.js"]);
+
+  // Existing files outside of the repository aren't reported
+  let outside = fixture
+    .path()
+    .join("src/index.js")
+    .to_str()
+    .unwrap()
+    .to_string();
+  let paths = vec![
+    outside,
+    "../src/index.js".to_string(),
+    "../package.json".to_string(),
+  ];
+  let paths = filter_existing_paths(format!("{}/src", repo), paths);
+  assert!(paths.is_empty(), "{:?}", paths);
+}
+
+/// Returns files matching `glob`. When `repoDir` points to a file
+/// rather than a directory, only that file is matched, by its name.
+#[napi]
+pub fn walk_repo_glob(repo_dir: String, glob: String, options: Option<WalkOptions>) -> Vec<String> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return vec![];
+  };
+
+  walk_repo_paths(&repo_dir, &options, |path, _| {
+    if matcher.is_match(path) {
+      Some(path.to_string())
+    } else {
+      None
+    }
+  })
+}
+
+#[test]
+pub fn test_walk_repo_glob() {
+  let repo = ".".to_string();
+  let glob = "*.json".to_string();
+  let paths = walk_repo_glob(repo, glob, None);
+  for path in paths {
+    println!("{}", path);
+  }
+}
+
+#[test]
+fn test_walk_repo_glob_slash_separators() {
+  let fixture = make_fixture(&["src/config.json", "config.json"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let paths = walk_repo_glob(repo, "src/*.json".to_string(), None);
+  assert_eq!(paths, vec!["src/config.json".to_string()]);
+}
+
+#[test]
+fn test_walk_repo_glob_hidden_dirs() {
+  let fixture = make_fixture(&[
+    ".github/workflows/build.yml",
+    ".vscode/settings.json",
+    "src/.hidden/build.yml",
+    "build.yml",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+
+  let paths = walk_repo_glob(repo.clone(), "**/.github/**".to_string(), None);
+  assert_eq!(paths, vec![".github/workflows/build.yml".to_string()]);
+
+  let paths = walk_repo_glob(repo.clone(), ".vscode/*.json".to_string(), None);
+  assert_eq!(paths, vec![".vscode/settings.json".to_string()]);
+
+  // Wildcards match leading dots as well, no explicit `.` segment is required
+  let paths = walk_repo_glob(repo, "**/*.yml".to_string(), None);
+  assert_eq!(
+    paths,
+    vec![
+      "build.yml".to_string(),
+      ".github/workflows/build.yml".to_string(),
+      "src/.hidden/build.yml".to_string(),
+    ]
+  );
+}
+
+#[test]
+fn test_walk_repo_glob_repo_root_relative() {
+  let fixture = make_fixture(&["packages/a/package.json", "packages/b/package.json"]);
+  let repo = fixture
+    .path()
+    .join("packages/a")
+    .to_str()
+    .unwrap()
+    .to_string();
+  let options = Some(WalkOptions {
+    repo_root_relative: Some(true),
+    ..Default::default()
+  });
+
+  // Not a git repository yet, paths stay relative to `repo_dir`
+  let paths = walk_repo_glob(repo.clone(), "**/package.json".to_string(), options.clone());
+  assert_eq!(paths, vec!["package.json".to_string()]);
+
+  gix::init(fixture.path()).unwrap();
+
+  let paths = walk_repo_glob(repo.clone(), "**/package.json".to_string(), None);
+  assert_eq!(paths, vec!["package.json".to_string()]);
+
+  let paths = walk_repo_glob(repo, "packages/a/*.json".to_string(), options);
+  assert_eq!(paths, vec!["packages/a/package.json".to_string()]);
+}
+
+#[test]
+fn test_walk_repo_glob_prune_dirs() {
+  let fixture = make_fixture(&[
+    "src/lib.rs",
+    "target/debug/build.rs",
+    "crates/foo/target/gen.rs",
+    "crates/foo/src/target.rs",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let options = Some(WalkOptions {
+    prune_dirs: Some(vec!["target".to_string()]),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo, "**/*.rs".to_string(), options);
+  assert_eq!(
+    paths,
+    vec![
+      "crates/foo/src/target.rs".to_string(),
+      "src/lib.rs".to_string()
+    ]
+  );
+}
+
+#[test]
+fn test_walk_repo_glob_double_star_in_middle() {
+  let fixture = make_fixture(&[
+    "src/test/zero.rs",
+    "src/a/test/one.rs",
+    "src/a/b/c/test/many.rs",
+    "src/testing/other.rs",
+    "test/root.rs",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let paths = walk_repo_glob(repo, "src/**/test/*.rs".to_string(), None);
+  assert_eq!(
+    paths,
+    vec![
+      "src/a/b/c/test/many.rs".to_string(),
+      "src/a/test/one.rs".to_string(),
+      "src/test/zero.rs".to_string(),
+    ]
+  );
+}
+
+#[test]
+fn test_walk_repo_glob_dir_as_recursive() {
+  let fixture = make_fixture(&["src/lib.rs", "src/a/mod.rs", "srcfile", "lib/src"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+
+  let paths = walk_repo_glob(repo.clone(), "src".to_string(), None);
+  assert!(paths.is_empty());
+
+  let options = Some(WalkOptions {
+    dir_as_recursive: Some(true),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo.clone(), "src".to_string(), options.clone());
+  assert_eq!(
+    paths,
+    vec!["src/lib.rs".to_string(), "src/a/mod.rs".to_string()]
+  );
+
+  // Files are matched as usual
+  let paths = walk_repo_glob(repo, "lib/src".to_string(), options);
+  assert_eq!(paths, vec!["lib/src".to_string()]);
+}
+
+#[test]
+fn test_walk_repo_glob_unique_sorted() {
+  let fixture = make_fixture(&["b/a.json", "b.json", "a/z.json", "a.json"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+
+  let paths = walk_repo_glob(repo.clone(), "**/*.json".to_string(), None);
+  assert_eq!(paths, vec!["a.json", "b.json", "a/z.json", "b/a.json"]);
+
+  let options = Some(WalkOptions {
+    unique_sorted: Some(true),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo, "**/*.json".to_string(), options);
+  assert_eq!(paths, vec!["a.json", "a/z.json", "b.json", "b/a.json"]);
+}
+
+#[test]
+fn test_walk_repo_glob_basename_case_insensitive() {
+  let fixture = make_fixture(&["Makefile", "a/makefile", "b/MAKEFILE", "b/Makefile.am"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+
+  let options = Some(WalkOptions {
+    match_basename: Some(true),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo.clone(), "Makefile".to_string(), options);
+  assert_eq!(paths, vec!["Makefile".to_string()]);
+
+  let options = Some(WalkOptions {
+    match_basename: Some(true),
+    case_insensitive: Some(true),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo, "Makefile".to_string(), options);
+  assert_eq!(
+    paths,
+    vec![
+      "Makefile".to_string(),
+      "a/makefile".to_string(),
+      "b/MAKEFILE".to_string()
+    ]
+  );
+}
+
+#[test]
+fn test_walk_repo_glob_question_mark() {
+  let fixture = make_fixture(&[
+    "file.json",
+    "file1.json",
+    "fileA.json",
+    "file12.json",
+    "a/b.json",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let paths = walk_repo_glob(repo.clone(), "file?.json".to_string(), None);
+  assert_eq!(paths, vec!["file1.json", "fileA.json"]);
+
+  // Like `*`, `?` matches `/` unless `literalSeparator` is set
+  let paths = walk_repo_glob(repo.clone(), "a?b.json".to_string(), None);
+  assert_eq!(paths, vec!["a/b.json"]);
+  let spec = GlobSpec {
+    glob: "a?b.json".to_string(),
+    case_insensitive: None,
+    literal_separator: Some(true),
+  };
+  assert!(walk_repo_globs_opts(repo, vec![spec], None).is_empty());
+}
+
+#[test]
+fn test_walk_repo_glob_skip_submodule_check() {
+  let fixture = make_fixture(&[
+    "package.json",
+    "lib/package.json",
+    "vendor/dep/package.json",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let glob = "**/package.json".to_string();
+  let options = Some(WalkOptions {
+    skip_submodule_check: Some(true),
+    ..Default::default()
+  });
+
+  let expected = walk_repo_glob(repo.clone(), glob.clone(), None);
+  assert_eq!(expected.len(), 3);
+  let paths = walk_repo_glob(repo.clone(), glob.clone(), options.clone());
+  assert_eq!(paths, expected);
+
+  std::fs::write(
+    fixture.path().join(".gitmodules"),
+    "[submodule \"dep\"]\n  path = vendor/dep\n",
+  )
+  .unwrap();
+  let paths = walk_repo_glob(repo.clone(), glob.clone(), None);
+  assert_eq!(paths, vec!["package.json", "lib/package.json"]);
+  let paths = walk_repo_glob(repo, glob, options);
+  assert_eq!(paths, expected);
+}
+
+#[test]
+fn test_walk_repo_glob_unsorted() {
+  let fixture = make_fixture(&["b.json", "a.json", "c/d.json", "c/a.json", "e/f/g.json"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let glob = "**/*.json".to_string();
+  let sorted = walk_repo_glob(repo.clone(), glob.clone(), None);
+  let options = Some(WalkOptions {
+    sort: Some(false),
+    ..Default::default()
+  });
+  let mut unsorted = walk_repo_glob(repo, glob, options);
+  unsorted.sort();
+  let mut expected = sorted.clone();
+  expected.sort();
+  assert_eq!(unsorted, expected);
+  assert_eq!(unsorted.len(), 5);
+}
+
+#[test]
+fn test_walk_repo_glob_relative_to() {
+  let fixture = make_fixture(&[
+    "package.json",
+    "packages/a/x.json",
+    "packages/b/y.json",
+    "packages-lock.json",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let options = Some(WalkOptions {
+    relative_to: Some("packages/".to_string()),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo, "**/*.json".to_string(), options);
+  assert_eq!(paths, vec!["a/x.json", "b/y.json"]);
+}
+
+#[test]
+fn test_walk_repo_glob_exclude_extensions() {
+  let fixture = make_fixture(&[
+    ".lock",
+    "yarn.lock",
+    "src/app.js",
+    "src/app.min.js",
+    "src/vendor.MIN.JS",
+    "src/style.css",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let options = WalkOptions {
+    exclude_extensions: Some(vec!["lock".to_string(), ".min.js".to_string()]),
+    ..Default::default()
+  };
+  let paths = walk_repo_glob(repo.clone(), "**".to_string(), Some(options.clone()));
+  assert_eq!(
+    paths,
+    vec![".lock", "src/app.js", "src/style.css", "src/vendor.MIN.JS"]
+  );
+
+  let options = WalkOptions {
+    case_insensitive: Some(true),
+    ..options
+  };
+  let paths = walk_repo_glob(repo, "**/*.js".to_string(), Some(options));
+  assert_eq!(paths, vec!["src/app.js"]);
+}
+
+#[test]
+fn test_walk_repo_glob_exclude_binary_extensions() {
+  let fixture = make_fixture(&[
+    "logo.png",
+    "README.md",
+    "docs/img/logo.png",
+    "docs/index.md",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let options = WalkOptions {
+    exclude_extensions: Some(vec!["png".to_string(), "zip".to_string()]),
+    ..Default::default()
+  };
+  let paths = walk_repo_glob(repo, "**/*".to_string(), Some(options));
+  assert_eq!(paths, vec!["README.md", "docs/index.md"]);
+}
+
+#[test]
+fn test_walk_repo_glob_stop_at_marker() {
+  let fixture = make_fixture(&[
+    "README.md",
+    "app/package.json",
+    "app/src/index.js",
+    "app/packages/nested/package.json",
+    "libs/a/package.json",
+    "libs/a/b/package.json",
+    "libs/c/d/package.json",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let options = Some(WalkOptions {
+    stop_at_marker: Some("package.json".to_string()),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo, "**".to_string(), options);
+  assert_eq!(
+    paths,
+    vec![
+      "README.md",
+      "app/package.json",
+      "libs/a/package.json",
+      "libs/c/d/package.json",
+    ]
+  );
+}
+
+#[test]
+fn test_walk_repo_glob_extglob() {
+  let fixture = make_fixture(&["foo.json", "bar.json", "baz.json", "foo.yml"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let options = Some(WalkOptions {
+    extglob: Some(true),
+    ..Default::default()
+  });
+  let glob = "@(foo|bar).json".to_string();
+  let paths = walk_repo_glob(repo.clone(), glob.clone(), options.clone());
+  assert_eq!(paths, vec!["bar.json", "foo.json"]);
+
+  assert!(walk_repo_glob(repo.clone(), glob, None).is_empty());
+  assert!(walk_repo_glob(repo, "!(foo).json".to_string(), options).is_empty());
+}
+
+#[test]
+fn test_walk_repo_glob_mount_prefix() {
+  let fixture = make_fixture(&["package.json", "src/index.ts", "src/a/b.ts"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let options = Some(WalkOptions {
+    mount_prefix: Some("/mnt/overlay/repo/".to_string()),
+    ..Default::default()
+  });
+  let glob = "/mnt/overlay/repo/src/**".to_string();
+  let paths = walk_repo_glob(repo.clone(), glob.clone(), options.clone());
+  assert_eq!(paths, vec!["src/index.ts", "src/a/b.ts"]);
+
+  assert!(walk_repo_glob(repo.clone(), glob, None).is_empty());
+  assert!(walk_repo_glob(repo, "src/**".to_string(), options).is_empty());
+}
+
+#[test]
+fn test_walk_repo_glob_anchored() {
+  let fixture = make_fixture(&[
+    "package.json",
+    "a/package.json",
+    "src/index.ts",
+    "src/a/index.ts",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let paths = walk_repo_glob(repo.clone(), "/package.json".to_string(), None);
+  assert_eq!(paths, vec!["package.json"]);
+
+  let paths = walk_repo_glob(repo.clone(), "/src/index.ts".to_string(), None);
+  assert_eq!(paths, vec!["src/index.ts"]);
+
+  let options = Some(WalkOptions {
+    match_basename: Some(true),
+    ..Default::default()
+  });
+  assert!(walk_repo_glob(repo, "/package.json".to_string(), options).is_empty());
+}
+
+#[test]
+fn test_walk_repo_glob_dot_slash() {
+  let fixture = make_fixture(&["Cargo.toml", "sub/Cargo.toml", "src/main.rs"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let paths = walk_repo_glob(repo.clone(), "./Cargo.toml".to_string(), None);
+  assert_eq!(paths, vec!["Cargo.toml"]);
+
+  let paths = walk_repo_glob(repo, "./src/*.rs".to_string(), None);
+  assert_eq!(paths, vec!["src/main.rs"]);
+
+  let paths = vec!["./Cargo.toml".to_string(), "./sub/Cargo.toml".to_string()];
+  let globs = vec!["./Cargo.toml".to_string()];
+  assert_eq!(
+    match_paths_against_globs(paths, globs, None),
+    vec!["Cargo.toml"]
+  );
+}
+
+#[test]
+fn test_walk_repo_glob_ignore_files() {
+  let fixture = make_fixture(&["a/x.json", "b/x.json", "c/x.json"]);
+  std::fs::write(fixture.path().join(".ignore"), "a/\n").unwrap();
+  std::fs::write(fixture.path().join(".rgignore"), "b/\n").unwrap();
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let glob = "**/*.json".to_string();
+
+  // `.ignore` is honored with `useIgnoreFiles` unset
+  let paths = walk_repo_glob(repo.clone(), glob.clone(), None);
+  assert_eq!(paths, vec!["b/x.json", "c/x.json"]);
+
+  let options = Some(WalkOptions {
+    use_rgignore: Some(true),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo.clone(), glob.clone(), options);
+  assert_eq!(paths, vec!["c/x.json"]);
+
+  let options = Some(WalkOptions {
+    use_ignore_files: Some(false),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo, glob, options);
+  assert_eq!(paths, vec!["a/x.json", "b/x.json", "c/x.json"]);
+}
+
+#[test]
+fn test_walk_repo_glob_skip_empty() {
+  let fixture = make_fixture(&["empty.json", "dir/.gitkeep"]);
+  std::fs::write(fixture.path().join("config.json"), "{}").unwrap();
+  let repo = fixture.path().to_str().unwrap().to_string();
+
+  let paths = walk_repo_glob(repo.clone(), "**".to_string(), None);
+  assert_eq!(paths, vec!["config.json", "empty.json", "dir/.gitkeep"]);
+
+  let options = Some(WalkOptions {
+    skip_empty: Some(true),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo, "**".to_string(), options);
+  assert_eq!(paths, vec!["config.json"]);
+}
+
+#[test]
+fn test_walk_repo_glob_unicode_normalize() {
+  let fixture = make_fixture(&["cafe\u{301}.txt", "cafe.txt"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let glob = "caf\u{e9}.*".to_string();
+
+  assert!(walk_repo_glob(repo.clone(), glob.clone(), None).is_empty());
+
+  let options = Some(WalkOptions {
+    unicode_normalize: Some(true),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo.clone(), glob, options.clone());
+  assert_eq!(paths, vec!["cafe\u{301}.txt"]);
+
+  // Decomposed patterns match composed names as well
+  std::fs::write(fixture.path().join("na\u{ef}ve.md"), "").unwrap();
+  let paths = walk_repo_glob(repo, "nai\u{308}ve.md".to_string(), options);
+  assert_eq!(paths, vec!["na\u{ef}ve.md"]);
+}
+
+#[test]
+fn test_walk_repo_glob_lowercase_output() {
+  let fixture = make_fixture(&["Docs/README.md", "CHANGELOG.md", "src/Main.rs"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let options = Some(WalkOptions {
+    lowercase_output: Some(true),
+    unique_sorted: Some(true),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo.clone(), "**/*.md".to_string(), options);
+  assert_eq!(paths, vec!["changelog.md", "docs/readme.md"]);
+
+  // Matching is still case-sensitive
+  let options = Some(WalkOptions {
+    lowercase_output: Some(true),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo, "Docs/*".to_string(), options);
+  assert_eq!(paths, vec!["docs/readme.md"]);
+}
+
+#[test]
+fn test_lowercase_output_typed_walks() {
+  let fixture = make_fixture(&["Docs/Guide.MD"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let glob = "**/*.MD".to_string();
+  let options = WalkOptions {
+    lowercase_output: Some(true),
+    ..Default::default()
+  };
+
+  let oids = walk_repo_glob_oids(repo.clone(), glob.clone(), Some(options.clone()));
+  assert_eq!(oids[0].path, "docs/guide.md");
+  let ancestors = walk_repo_glob_ancestors(repo.clone(), glob.clone(), Some(options.clone()));
+  assert_eq!(ancestors[0].path, "docs/guide.md");
+  assert_eq!(ancestors[0].ancestors, vec!["docs"]);
+  let newest = walk_repo_glob_newest_per_dir(repo.clone(), glob.clone(), Some(options.clone()));
+  assert_eq!(newest[0].path, "docs/guide.md");
+  let suggested =
+    walk_repo_glob_with_suggestions(repo.clone(), "src/*.MD".to_string(), Some(options.clone()));
+  assert_eq!(suggested.suggestions, vec!["docs/guide.md"]);
+  let extensions = walk_repo_glob_extensions(repo.clone(), glob.clone(), Some(options.clone()));
+  assert_eq!(extensions, vec!["md"]);
+
+  let streamed: Vec<String> = spawn_glob_walk(repo, glob, options, 1)
+    .into_iter()
+    .collect();
+  assert_eq!(streamed, vec!["docs/guide.md"]);
+}
+
+#[test]
+fn test_walk_repo_glob_scanignore() {
+  let fixture = make_fixture(&[
+    "src/app.ts",
+    "src/app.test.ts.snap",
+    "src/keep.snap",
+    "fixtures/data.ts",
+    "lib/fixtures/data.ts",
+  ]);
+  std::fs::write(
+    fixture.path().join(".scanignore"),
+    "/fixtures/\n*.snap\n!keep.snap\n",
+  )
+  .unwrap();
+  let repo = fixture.path().to_str().unwrap().to_string();
+
+  let paths = walk_repo_glob(repo.clone(), "**/*.{ts,snap}".to_string(), None);
+  assert_eq!(paths.len(), 5);
+
+  let options = Some(WalkOptions {
+    use_scanignore: Some(true),
+    unique_sorted: Some(true),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo, "**/*.{ts,snap}".to_string(), options.clone());
+  assert_eq!(
+    paths,
+    vec!["lib/fixtures/data.ts", "src/app.ts", "src/keep.snap"]
+  );
+
+  let fixture = make_fixture(&["a.ts"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  assert_eq!(
+    walk_repo_glob(repo, "*.ts".to_string(), options),
+    vec!["a.ts"]
+  );
+}
+
+#[test]
+fn test_walk_repo_glob_exclude_paths() {
+  let fixture = make_fixture(&["a.json", "b.json", "sub/a.json", "sub/c.json"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let options = Some(WalkOptions {
+    exclude_paths: Some(vec![
+      "a.json".to_string(),
+      "sub/c.json".to_string(),
+      "missing.json".to_string(),
+    ]),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo.clone(), "**/*.json".to_string(), options);
+  assert_eq!(paths, vec!["b.json", "sub/a.json"]);
+
+  // Compared against paths as returned, i.e. relative to `relativeTo`
+  let options = Some(WalkOptions {
+    relative_to: Some("sub".to_string()),
+    exclude_paths: Some(vec!["a.json".to_string()]),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo, "**/*.json".to_string(), options);
+  assert_eq!(paths, vec!["c.json"]);
+}
+
+#[test]
+fn test_walk_repo_glob_max_entries_per_second() {
+  let fixture = make_fixture(&["a.json", "b.json", "c/d.json", "c/e.json", "f/g.txt"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let options = Some(WalkOptions {
+    max_entries_per_second: Some(20),
+    ..Default::default()
+  });
+  let start = std::time::Instant::now();
+  let paths = walk_repo_glob(repo.clone(), "**/*.json".to_string(), options);
+  // 8 entries including the root and 2 directories, the first one isn't delayed
+  assert!(start.elapsed() >= std::time::Duration::from_millis(350));
+  assert_eq!(paths, walk_repo_glob(repo, "**/*.json".to_string(), None));
+}
+
+#[test]
+fn test_walk_repo_glob_file_repo_dir() {
+  let fixture = make_fixture(&["package.json", "sub/package.json"]);
+  let repo = fixture
+    .path()
+    .join("package.json")
+    .to_str()
+    .unwrap()
+    .to_string();
+
+  let paths = walk_repo_glob(repo.clone(), "*.json".to_string(), None);
+  assert_eq!(paths, vec!["package.json"]);
+
+  let paths = walk_repo_glob(repo, "*.yml".to_string(), None);
+  assert!(paths.is_empty());
+}
+
+#[cfg(windows)]
+#[test]
+fn test_walk_repo_glob_windows_separators() {
+  let fixture = make_fixture(&["src\\config.json", "config.json"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let paths = walk_repo_glob(repo, "src/*.json".to_string(), None);
+  assert_eq!(paths, vec!["src/config.json".to_string()]);
+}
+
+#[cfg(windows)]
+#[test]
+fn test_walk_repo_glob_native_separators() {
+  let fixture = make_fixture(&["src\\app\\config.json", "config.json"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let options = Some(WalkOptions {
+    native_separators: Some(true),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob(repo, "src/**/*.json".to_string(), options);
+  assert_eq!(paths, vec!["src\\app\\config.json".to_string()]);
+}
+
+#[cfg(windows)]
+#[test]
+fn test_native_separators_typed_walks() {
+  let fixture = make_fixture(&["src\\app\\config.json"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let glob = "src/**/*.json".to_string();
+  let options = WalkOptions {
+    native_separators: Some(true),
+    ..Default::default()
+  };
+
+  let depths = walk_repo_glob_depths(repo.clone(), glob.clone(), Some(options.clone()));
+  assert_eq!(depths[0].path, "src\\app\\config.json");
+  let ancestors = walk_repo_glob_ancestors(repo.clone(), glob.clone(), Some(options.clone()));
+  assert_eq!(ancestors[0].ancestors, vec!["src", "src\\app"]);
+  let streamed: Vec<String> = spawn_glob_walk(repo, glob, options.clone(), 1)
+    .into_iter()
+    .collect();
+  assert_eq!(streamed, vec!["src\\app\\config.json"]);
+
+  // Given paths come back unchanged
+  let paths = vec!["a::b".to_string(), "a/b".to_string()];
+  let matched = match_paths(paths, "a/*".to_string(), None, Some(options)).unwrap();
+  assert_eq!(matched, vec!["a/b"]);
+}
+
+/// Same as `walkRepoGlob`, but throws `RepoNotFound` when `repoDir`
+/// is not a directory instead of returning an empty list.
+#[napi]
+pub fn try_walk_repo_glob(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> napi::Result<Vec<String>, ScanError> {
+  if !Path::new(&repo_dir).is_dir() {
+    let reason = format!("Not a directory: {}", repo_dir);
+    return Err(scan_error(ScanError::RepoNotFound, reason));
+  }
+
+  Ok(walk_repo_glob(repo_dir, glob, options))
+}
+
+#[test]
+fn test_try_walk_repo_glob() {
+  let fixture = make_fixture(&["package.json"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let paths = try_walk_repo_glob(repo, "*.json".to_string(), None).unwrap();
+  assert_eq!(paths, vec!["package.json"]);
+
+  let missing = fixture.path().join("missing").to_str().unwrap().to_string();
+  let err = try_walk_repo_glob(missing.clone(), "*.json".to_string(), None).unwrap_err();
+  assert_eq!(err.status, ScanError::RepoNotFound);
+  assert_eq!(err.reason, format!("Not a directory: {}", missing));
+  assert!(walk_repo_glob(missing, "*.json".to_string(), None).is_empty());
+}
+
+/// Same as `walkRepoGlob`, but throws `RepoNotFound` when `repoDir` doesn't exist
+/// and `InvalidGlob` when `glob` can't be parsed.
+#[napi]
+pub fn walk_repo_glob_checked(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> napi::Result<Vec<String>, ScanError> {
+  check_repo_dir(&repo_dir)?;
+  check_globs(&[&glob])?;
+  check_gitmodules(&repo_dir, &options.clone().unwrap_or_default())?;
+  Ok(walk_repo_glob(repo_dir, glob, options))
+}
+
+#[test]
+fn test_walk_repo_glob_checked() {
+  let fixture = make_fixture(&["package.json"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let paths = walk_repo_glob_checked(repo.clone(), "*.json".to_string(), None).unwrap();
+  assert_eq!(paths, vec!["package.json"]);
+
+  let err = walk_repo_glob_checked(repo, "[*.json".to_string(), None).unwrap_err();
+  assert_eq!(err.status, ScanError::InvalidGlob);
+  assert!(err.reason.contains("[*.json"), "{}", err.reason);
+
+  let missing = fixture.path().join("missing").to_str().unwrap().to_string();
+  let err = walk_repo_glob_checked(missing, "*.json".to_string(), None).unwrap_err();
+  assert_eq!(err.status, ScanError::RepoNotFound);
+}
+
+#[test]
+fn test_walk_repo_glob_checked_strict_submodules() {
+  let fixture = make_fixture(&["a.json", "vendor/dep/b.json"]);
+  std::fs::write(
+    fixture.path().join(".gitmodules"),
+    "[submodule \"dep\"\n  path = vendor/dep\n",
+  )
+  .unwrap();
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let glob = "**/*.json".to_string();
+
+  let paths = walk_repo_glob_checked(repo.clone(), glob.clone(), None).unwrap();
+  assert_eq!(paths, vec!["a.json", "vendor/dep/b.json"]);
+
+  let options = Some(WalkOptions {
+    strict_submodules: Some(true),
+    ..Default::default()
+  });
+  let err = walk_repo_glob_checked(repo.clone(), glob.clone(), options.clone()).unwrap_err();
+  assert_eq!(err.status, ScanError::ConfigParse);
+  let err = walk_repo_globs_checked(repo.clone(), vec![glob.clone()], options.clone()).unwrap_err();
+  assert_eq!(err.status, ScanError::ConfigParse);
+
+  std::fs::write(
+    fixture.path().join(".gitmodules"),
+    "[submodule \"dep\"]\n  path = vendor/dep\n",
+  )
+  .unwrap();
+  let paths = walk_repo_glob_checked(repo, glob, options).unwrap();
+  assert_eq!(paths, vec!["a.json"]);
+}
+
+/// Same as `walkRepoGlob`, but returns the paths as a JSON array string.
+/// For large results, a single `JSON.parse` is cheaper than converting
+/// every path into a JS string separately.
+#[napi]
+pub fn walk_repo_glob_json(repo_dir: String, glob: String, options: Option<WalkOptions>) -> String {
+  let paths = walk_repo_glob(repo_dir, glob, options);
+  serde_json::to_string(&paths).unwrap_or_else(|_| "[]".to_string())
+}
+
+#[test]
+fn test_walk_repo_glob_json() {
+  let fixture = make_fixture(&["package.json", "a/b.json", "b.txt"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let json = walk_repo_glob_json(repo.clone(), "**/*.json".to_string(), None);
+  let paths: Vec<String> = serde_json::from_str(&json).unwrap();
+  assert_eq!(paths, vec!["package.json", "a/b.json"]);
+  assert_eq!(
+    paths,
+    walk_repo_glob(repo.clone(), "**/*.json".to_string(), None)
+  );
+
+  assert_eq!(walk_repo_glob_json(repo, "*.md".to_string(), None), "[]");
+}
+
+/// Same as `walkRepoGlob`, but keeps only every `stride`-th match, e.g. the 100th,
+/// 200th and so on for a stride of 100, to sample large repositories.
+/// Matches are counted in the walk order.
+#[napi]
+pub fn walk_repo_glob_sampled(
+  repo_dir: String,
+  glob: String,
+  stride: Option<u32>,
+  options: Option<WalkOptions>,
+) -> Vec<String> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return vec![];
+  };
+
+  let stride = stride.unwrap_or(1).max(1);
+  let mut count = 0;
+  walk_repo_paths(&repo_dir, &options, |path, _| {
+    if !matcher.is_match(path) {
+      return None;
+    }
+
+    count += 1;
+    if count % stride == 0 {
+      Some(path.to_string())
+    } else {
+      None
+    }
+  })
+}
+
+#[test]
+fn test_walk_repo_glob_sampled() {
+  let fixture = make_fixture(&[
+    "a.json", "b.json", "c.json", "d/e.json", "d/f.json", "g.txt",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let glob = "**/*.json".to_string();
+  let paths = walk_repo_glob_sampled(repo.clone(), glob.clone(), Some(2), None);
+  assert_eq!(paths, vec!["b.json", "d/e.json"]);
+
+  let all = walk_repo_glob_sampled(repo.clone(), glob.clone(), None, None);
+  assert_eq!(all, walk_repo_glob(repo, glob, None));
+}
+
+#[napi(object)]
+pub struct BlobMatch {
+  pub path: String,
+  /// Hex id of the blob recorded in the git index, if the file is tracked
+  pub oid: Option<String>,
+}
+
+#[napi]
+pub fn walk_repo_glob_oids(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> Vec<BlobMatch> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return vec![];
+  };
+
+  let index = RepoIndex::open(&repo_dir, &options);
+  walk_repo(&repo_dir, &options, |path, _| {
+    if !matcher.is_match(path) {
+      return None;
+    }
+
+    let oid = index
+      .as_ref()
+      .and_then(|index| index.entry(path))
+      .map(|entry| entry.id.to_string());
+
+    Some(BlobMatch {
+      path: output_path(path.to_string(), &options),
+      oid,
+    })
+  })
+}
+
+#[test]
+fn test_walk_repo_glob_oids() {
+  let fixture = make_fixture(&["untracked.txt"]);
+  std::fs::write(fixture.path().join("hello.txt"), "hello\n").unwrap();
+  git(fixture.path(), &["init", "-q"]);
+  git(fixture.path(), &["add", "hello.txt"]);
+  git(fixture.path(), &["commit", "-q", "-m", "init"]);
+
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let matches = walk_repo_glob_oids(repo, "*.txt".to_string(), None);
+  assert_eq!(matches.len(), 2);
+  assert_eq!(matches[0].path, "hello.txt");
+  assert_eq!(
+    matches[0].oid.as_deref(),
+    Some("ce013625030ba8dba906f756967f9e9ca394464a")
+  );
+  assert_eq!(matches[1].path, "untracked.txt");
+  assert_eq!(matches[1].oid, None);
+}
+
+#[test]
+fn test_walk_repo_glob_oids_relative_to() {
+  let fixture = make_fixture(&[]);
+  std::fs::create_dir_all(fixture.path().join("sub")).unwrap();
+  std::fs::write(fixture.path().join("sub/hello.txt"), "hello\n").unwrap();
+  git(fixture.path(), &["init", "-q"]);
+  git(fixture.path(), &["add", "."]);
+
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let options = Some(WalkOptions {
+    relative_to: Some("sub".to_string()),
+    ..Default::default()
+  });
+  let matches = walk_repo_glob_oids(repo, "*.txt".to_string(), options);
+  assert_eq!(matches.len(), 1);
+  assert_eq!(matches[0].path, "hello.txt");
+  assert_eq!(
+    matches[0].oid.as_deref(),
+    Some("ce013625030ba8dba906f756967f9e9ca394464a")
+  );
+}
+
+#[napi(object)]
+pub struct TreeMatch {
+  pub path: String,
+  /// Blob size in bytes, set with `withSizes` unless the blob is missing
+  /// from the object database, e.g. in partial clones
+  pub size: Option<f64>,
+}
+
+/// Matches files of the tree at `rev`, e.g. `HEAD`, `main~2` or a tag, instead of
+/// the working tree. With `withSizes`, sizes are read from blob headers in the object
+/// database rather than by stat-ing files. As nothing is walked on disk, only options
+/// affecting matching apply. Throws `RepoNotFound` outside of a repository
+/// and `RevisionNotFound` when `rev` doesn't resolve to a tree.
+#[napi]
+pub fn walk_repo_glob_at(
+  repo_dir: String,
+  rev: String,
+  glob: String,
+  with_sizes: Option<bool>,
+  options: Option<WalkOptions>,
+) -> napi::Result<Vec<TreeMatch>, ScanError> {
+  let options = options.unwrap_or_default();
+  check_globs(&[&glob])?;
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return Ok(vec![]);
+  };
+
+  let Some((repo, _, prefix)) = discover_repo(Path::new(&repo_dir)) else {
+    let reason = format!("Not a git repository: {}", repo_dir);
+    return Err(scan_error(ScanError::RepoNotFound, reason));
+  };
+
+  let to_revision_error = |err: &dyn std::fmt::Display| {
+    let reason = format!("Failed to resolve {}: {}", rev, err);
+    scan_error(ScanError::RevisionNotFound, reason)
+  };
+  let tree = repo
+    .rev_parse_single(rev.as_str())
+    .map_err(|err| to_revision_error(&err))?
+    .object()
+    .map_err(|err| to_revision_error(&err))?
+    .peel_to_tree()
+    .map_err(|err| to_revision_error(&err))?;
+  let entries = tree
+    .traverse()
+    .breadthfirst
+    .files()
+    .map_err(|err| to_revision_error(&err))?;
+
+  // Tree paths are relative to the repository root
+  let strip_prefix = !prefix.is_empty() && options.repo_root_relative != Some(true);
+  let include_symlinks = options.include_symlinks == Some(true);
+  let mut matches = vec![];
+  for entry in entries {
+    let is_symlink = entry.mode == gix::object::tree::EntryMode::Link;
+    let is_file = entry.mode.is_blob() || include_symlinks && is_symlink;
+    let Ok(path) = std::str::from_utf8(&entry.filepath) else {
+      continue;
+    };
+
+    let path = if strip_prefix {
+      let Some(path) = path
+        .strip_prefix(prefix.as_str())
+        .and_then(|path| path.strip_prefix('/'))
+      else {
+        continue;
+      };
+      path
+    } else {
+      path
+    };
+
+    if !is_file || !matcher.is_match(path) {
+      continue;
+    }
+
+    let size = if with_sizes == Some(true) {
+      repo
+        .find_header(entry.oid)
+        .ok()
+        .map(|header| header.size() as f64)
+    } else {
+      None
+    };
+
+    matches.push(TreeMatch {
+      path: output_path(path.to_string(), &options),
+      size,
+    });
+  }
+
+  matches.sort_by(|a, b| walk_order_cmp(&a.path, &b.path));
+  Ok(matches)
+}
+
+#[test]
+fn test_walk_repo_glob_at() {
+  let fixture = make_fixture(&[]);
+  std::fs::create_dir_all(fixture.path().join("src")).unwrap();
+  std::fs::write(fixture.path().join("hello.txt"), "hello\n").unwrap();
+  std::fs::write(fixture.path().join("src/a.txt"), "0123456789").unwrap();
+  std::fs::write(fixture.path().join("src/b.md"), "").unwrap();
+  git(fixture.path(), &["init", "-q"]);
+  git(fixture.path(), &["add", "."]);
+  git(fixture.path(), &["commit", "-q", "-m", "init"]);
+
+  // The working tree doesn't affect results
+  std::fs::write(fixture.path().join("hello.txt"), "changed").unwrap();
+  std::fs::write(fixture.path().join("new.txt"), "").unwrap();
+
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let matches = walk_repo_glob_at(
+    repo.clone(),
+    "HEAD".to_string(),
+    "**/*.txt".to_string(),
+    Some(true),
+    None,
+  )
+  .unwrap();
+  let sizes: Vec<(&str, Option<f64>)> = matches.iter().map(|m| (m.path.as_str(), m.size)).collect();
+  assert_eq!(
+    sizes,
+    vec![("hello.txt", Some(6.0)), ("src/a.txt", Some(10.0))]
+  );
+
+  let subdir = fixture.path().join("src").to_str().unwrap().to_string();
+  let matches = walk_repo_glob_at(subdir, "HEAD".to_string(), "*".to_string(), None, None).unwrap();
+  let sizes: Vec<(&str, Option<f64>)> = matches.iter().map(|m| (m.path.as_str(), m.size)).collect();
+  assert_eq!(sizes, vec![("a.txt", None), ("b.md", None)]);
+
+  let err = walk_repo_glob_at(repo, "nope".to_string(), "*".to_string(), None, None).err();
+  assert_eq!(err.unwrap().status, ScanError::RevisionNotFound);
+}
+
+#[test]
+fn test_walk_repo_glob_at_walk_order() {
+  let fixture = make_fixture(&["a/c.txt", "b.txt", "a/b/d.txt"]);
+  git(fixture.path(), &["init", "-q"]);
+  git(fixture.path(), &["add", "."]);
+  git(fixture.path(), &["commit", "-q", "-m", "init"]);
+
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let walked = walk_repo_glob(repo.clone(), "**/*.txt".to_string(), None);
+  let matches =
+    walk_repo_glob_at(repo, "HEAD".to_string(), "**/*.txt".to_string(), None, None).unwrap();
+  let paths: Vec<&str> = matches.iter().map(|m| m.path.as_str()).collect();
+  assert_eq!(paths, vec!["b.txt", "a/c.txt", "a/b/d.txt"]);
+  assert_eq!(paths, walked);
+}
+
+#[napi(object)]
+pub struct TrackedMatch {
+  pub path: String,
+  /// Whether the file is present in the git index, i.e. committed or staged
+  pub tracked: bool,
+}
+
+/// Returns matched files tagged with whether git tracks them.
+/// Outside of a repository, every file is reported as untracked.
+#[napi]
+pub fn walk_repo_glob_tracked(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> Vec<TrackedMatch> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return vec![];
+  };
+
+  let index = RepoIndex::open(&repo_dir, &options);
+  walk_repo(&repo_dir, &options, |path, _| {
+    if !matcher.is_match(path) {
+      return None;
+    }
+
+    let tracked = index
+      .as_ref()
+      .is_some_and(|index| index.entry(path).is_some());
+
+    Some(TrackedMatch {
+      path: output_path(path.to_string(), &options),
+      tracked,
+    })
+  })
+}
+
+#[test]
+fn test_walk_repo_glob_tracked() {
+  let fixture = make_fixture(&[
+    "committed.txt",
+    "staged.txt",
+    "scratch.txt",
+    "sub/notes.txt",
+  ]);
+  git(fixture.path(), &["init", "-q"]);
+  git(fixture.path(), &["add", "committed.txt"]);
+  git(fixture.path(), &["commit", "-q", "-m", "init"]);
+  git(fixture.path(), &["add", "staged.txt"]);
+
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let matches = walk_repo_glob_tracked(repo, "**/*.txt".to_string(), None);
+  let tags: Vec<(&str, bool)> = matches
+    .iter()
+    .map(|m| (m.path.as_str(), m.tracked))
+    .collect();
+  assert_eq!(
+    tags,
+    vec![
+      ("committed.txt", true),
+      ("scratch.txt", false),
+      ("staged.txt", true),
+      ("sub/notes.txt", false),
+    ]
+  );
+}
+
+#[test]
+fn test_walk_repo_glob_tracked_relative_to() {
+  let fixture = make_fixture(&["sub/committed.txt", "sub/scratch.txt"]);
+  git(fixture.path(), &["init", "-q"]);
+  git(fixture.path(), &["add", "sub/committed.txt"]);
+
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let options = Some(WalkOptions {
+    relative_to: Some("sub".to_string()),
+    ..Default::default()
+  });
+  let matches = walk_repo_glob_tracked(repo, "*.txt".to_string(), options);
+  let tags: Vec<(&str, bool)> = matches
+    .iter()
+    .map(|m| (m.path.as_str(), m.tracked))
+    .collect();
+  assert_eq!(tags, vec![("committed.txt", true), ("scratch.txt", false)]);
+}
+
+#[napi(object)]
+pub struct ExecutableMatch {
+  pub path: String,
+  /// Whether the git index records the file as executable (mode 100755),
+  /// regardless of its permissions in the working tree
+  pub git_executable: bool,
+}
+
+/// Returns matched files tagged with their executable bit from the git index.
+/// Untracked files, and every file outside of a repository, are reported as not executable.
+#[napi]
+pub fn walk_repo_glob_git_executable(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> Vec<ExecutableMatch> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return vec![];
+  };
+
+  let index = RepoIndex::open(&repo_dir, &options);
+  walk_repo(&repo_dir, &options, |path, _| {
+    if !matcher.is_match(path) {
+      return None;
+    }
+
+    let git_executable = index
+      .as_ref()
+      .and_then(|index| index.entry(path))
+      .is_some_and(|entry| entry.mode == gix::index::entry::Mode::FILE_EXECUTABLE);
+
+    Some(ExecutableMatch {
+      path: output_path(path.to_string(), &options),
+      git_executable,
+    })
+  })
+}
+
+#[test]
+fn test_walk_repo_glob_git_executable() {
+  let fixture = make_fixture(&["build.sh", "lib.sh", "scratch.sh"]);
+  git(fixture.path(), &["init", "-q"]);
+  git(fixture.path(), &["add", "build.sh", "lib.sh"]);
+  // Only the index is changed, the working tree file keeps its permissions
+  git(fixture.path(), &["update-index", "--chmod=+x", "build.sh"]);
+
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let matches = walk_repo_glob_git_executable(repo, "*.sh".to_string(), None);
+  let tags: Vec<(&str, bool)> = matches
+    .iter()
+    .map(|m| (m.path.as_str(), m.git_executable))
+    .collect();
+  assert_eq!(
+    tags,
+    vec![("build.sh", true), ("lib.sh", false), ("scratch.sh", false)]
+  );
+}
+
+#[test]
+fn test_walk_repo_glob_git_executable_relative_to() {
+  let fixture = make_fixture(&["scripts/build.sh", "scripts/lib.sh"]);
+  git(fixture.path(), &["init", "-q"]);
+  git(fixture.path(), &["add", "."]);
+  git(
+    fixture.path(),
+    &["update-index", "--chmod=+x", "scripts/build.sh"],
+  );
+
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let options = Some(WalkOptions {
+    relative_to: Some("scripts".to_string()),
+    ..Default::default()
+  });
+  let matches = walk_repo_glob_git_executable(repo, "*.sh".to_string(), options);
+  let tags: Vec<(&str, bool)> = matches
+    .iter()
+    .map(|m| (m.path.as_str(), m.git_executable))
+    .collect();
+  assert_eq!(tags, vec![("build.sh", true), ("lib.sh", false)]);
+}
+
+#[napi(object)]
+pub struct SubmoduleMatch {
+  /// Relative to the containing submodule, or to `repoDir` in the main tree
+  pub path: String,
+  /// Path of the containing submodule as listed in `.gitmodules`
+  pub submodule: Option<String>,
+}
+
+/// Walks into submodules as well, reporting their matches relative to the submodule root.
+/// The glob is still matched against paths relative to `repoDir`.
+/// Only submodules listed in the top-level `.gitmodules` are recognized.
+#[napi]
+pub fn walk_repo_glob_submodules(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> Vec<SubmoduleMatch> {
+  let options = WalkOptions {
+    skip_submodule_check: Some(true),
+    ..options.unwrap_or_default()
+  };
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return vec![];
+  };
+
+  let repo_path = Path::new(&repo_dir);
+  let repo_root = if options.repo_root_relative == Some(true) {
+    get_repo_root(repo_path).map(|(root, _)| root)
+  } else {
+    None
+  };
+  let mut submodules =
+    get_submodule_paths(repo_root.as_deref().unwrap_or(repo_path)).unwrap_or_default();
+  // Nested submodules take precedence over the ones containing them
+  submodules.sort_by_key(|submodule| std::cmp::Reverse(submodule.len()));
+
+  let relative_to = relative_to_dir(&options).unwrap_or_default();
+  walk_repo(&repo_dir, &options, |path, _| {
+    if !matcher.is_match(path) {
+      return None;
+    }
+
+    // `.gitmodules` paths don't have `relativeTo` stripped
+    let full_path = join_slash_path(relative_to, path);
+    let containing = submodules.iter().find_map(|submodule| {
+      let relative = full_path
+        .strip_prefix(submodule.as_str())?
+        .strip_prefix('/')?;
+      Some((submodule, relative))
+    });
+
+    Some(match containing {
+      Some((submodule, relative)) => SubmoduleMatch {
+        path: output_path(relative.to_string(), &options),
+        submodule: Some(output_path(submodule.clone(), &options)),
+      },
+      None => SubmoduleMatch {
+        path: output_path(path.to_string(), &options),
+        submodule: None,
+      },
+    })
+  })
+}
+
+#[test]
+fn test_walk_repo_glob_submodules() {
+  let fixture = make_fixture(&[
+    "package.json",
+    "vendor/dep/package.json",
+    "vendor/dep/lib/package.json",
+    "vendor/dependency/package.json",
+  ]);
+  std::fs::write(
+    fixture.path().join(".gitmodules"),
+    "[submodule \"dep\"]\n  path = vendor/dep\n",
+  )
+  .unwrap();
+
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let matches = walk_repo_glob_submodules(repo, "**/package.json".to_string(), None);
+  let tags: Vec<(&str, Option<&str>)> = matches
+    .iter()
+    .map(|m| (m.path.as_str(), m.submodule.as_deref()))
+    .collect();
+  assert_eq!(
+    tags,
+    vec![
+      ("package.json", None),
+      ("package.json", Some("vendor/dep")),
+      ("lib/package.json", Some("vendor/dep")),
+      ("vendor/dependency/package.json", None),
+    ]
+  );
+}
+
+#[test]
+fn test_walk_repo_glob_submodules_relative_to() {
+  let fixture = make_fixture(&["vendor/dep/package.json", "vendor/package.json"]);
+  std::fs::write(
+    fixture.path().join(".gitmodules"),
+    "[submodule \"dep\"]\n  path = vendor/dep\n",
+  )
+  .unwrap();
+
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let options = Some(WalkOptions {
+    relative_to: Some("vendor".to_string()),
+    ..Default::default()
+  });
+  let matches = walk_repo_glob_submodules(repo, "**/package.json".to_string(), options);
+  let tags: Vec<(&str, Option<&str>)> = matches
+    .iter()
+    .map(|m| (m.path.as_str(), m.submodule.as_deref()))
+    .collect();
+  assert_eq!(
+    tags,
+    vec![("package.json", None), ("package.json", Some("vendor/dep")),]
+  );
+}
+
+fn parent_dir(path: &str) -> &str {
+  path.rsplit_once('/').map_or("", |(parent, _)| parent)
+}
+
+/// Returns matched files which are untracked, staged or modified according to git.
+///
+/// Modifications are detected by comparing content hashes with the index,
+/// without applying git filters such as line ending conversion.
+/// Deleted files aren't reported, as there's nothing to walk.
+#[napi]
+pub fn walk_repo_glob_dirty(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> Vec<String> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return vec![];
+  };
+
+  let Some(index) = RepoIndex::open(&repo_dir, &options) else {
+    return vec![];
+  };
+
+  let head_blobs = index.head_blobs();
+  walk_repo_paths(&repo_dir, &options, |path, entry| {
+    if !matcher.is_match(path) {
+      return None;
+    }
+
+    let is_dirty = match index.entry(path) {
+      None => true,
+      Some(index_entry) => {
+        head_blobs.get(path) != Some(&index_entry.id)
+          || index.is_modified(index_entry, entry.path())
+      }
+    };
+
+    if is_dirty {
+      Some(path.to_string())
+    } else {
+      None
+    }
+  })
+}
+
+#[test]
+fn test_walk_repo_glob_dirty() {
+  let fixture = make_fixture(&[]);
+  let repo_path = fixture.path();
+  for file in ["modified.txt", "clean.txt", "sub/clean.txt"] {
+    std::fs::create_dir_all(repo_path.join(file).parent().unwrap()).unwrap();
+    std::fs::write(repo_path.join(file), "initial").unwrap();
+  }
+  git(repo_path, &["init", "-q"]);
+  git(repo_path, &["add", "."]);
+  git(repo_path, &["commit", "-q", "-m", "init"]);
+
+  let repo = repo_path.to_str().unwrap().to_string();
+  let paths = walk_repo_glob_dirty(repo.clone(), "**/*.txt".to_string(), None);
+  assert!(paths.is_empty());
+
+  std::fs::write(repo_path.join("modified.txt"), "changed").unwrap();
+  std::fs::write(repo_path.join("staged.txt"), "new").unwrap();
+  std::fs::write(repo_path.join("sub/untracked.txt"), "new").unwrap();
+  std::fs::write(repo_path.join("untracked.md"), "new").unwrap();
+  git(repo_path, &["add", "staged.txt"]);
+
+  let paths = walk_repo_glob_dirty(repo, "**/*.txt".to_string(), None);
+  assert_eq!(
+    paths,
+    vec!["modified.txt", "staged.txt", "sub/untracked.txt"]
+  );
+}
+
+#[test]
+fn test_walk_repo_glob_dirty_relative_to() {
+  let fixture = make_fixture(&[]);
+  let repo_path = fixture.path();
+  std::fs::create_dir_all(repo_path.join("sub")).unwrap();
+  for file in ["sub/modified.txt", "sub/clean.txt"] {
+    std::fs::write(repo_path.join(file), "initial").unwrap();
+  }
+  git(repo_path, &["init", "-q"]);
+  git(repo_path, &["add", "."]);
+  git(repo_path, &["commit", "-q", "-m", "init"]);
+  std::fs::write(repo_path.join("sub/modified.txt"), "changed").unwrap();
+
+  let repo = repo_path.to_str().unwrap().to_string();
+  let options = Some(WalkOptions {
+    relative_to: Some("sub".to_string()),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob_dirty(repo, "*.txt".to_string(), options);
+  assert_eq!(paths, vec!["modified.txt"]);
+}
+
+/// Returns unique directories containing matched files,
+/// with the empty string standing for the root directory.
+#[napi]
+pub fn walk_repo_glob_parents(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> Vec<String> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return vec![];
+  };
+
+  let parents = walk_repo(&repo_dir, &options, |path, _| {
+    if matcher.is_match(path) {
+      Some(parent_dir(path).to_string())
+    } else {
+      None
+    }
+  });
+
+  let mut seen = HashSet::new();
+  let parents = parents
+    .into_iter()
+    .filter(|parent| seen.insert(parent.clone()))
+    .collect();
+  finish_paths(parents, &options)
+}
+
+#[test]
+fn test_walk_repo_glob_parents() {
+  let fixture = make_fixture(&[
+    "package.json",
+    "packages/a/package.json",
+    "packages/a/src/index.js",
+    "packages/b/package.json",
+    "packages/b/tsconfig.json",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let parents = walk_repo_glob_parents(repo, "**/*.json".to_string(), None);
+  assert_eq!(
+    parents,
+    vec![
+      "".to_string(),
+      "packages/a".to_string(),
+      "packages/b".to_string()
+    ]
+  );
+}
+
+/// Returns unique directories directly containing a file whose name matches `glob`,
+/// e.g. package directories for `package.json`. Unlike in `walkRepoGlobParents`,
+/// `glob` is matched against file names rather than paths.
+#[napi]
+pub fn walk_repo_dirs_containing(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> Vec<String> {
+  let options = WalkOptions {
+    match_basename: Some(true),
+    ..options.unwrap_or_default()
+  };
+  walk_repo_glob_parents(repo_dir, glob, Some(options))
+}
+
+#[test]
+fn test_walk_repo_dirs_containing() {
+  let fixture = make_fixture(&[
+    "package.json",
+    "docs/README.md",
+    "packages/a/package.json",
+    "packages/a/src/index.js",
+    "packages/b/nested/deep/package.json",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let dirs = walk_repo_dirs_containing(repo, "package.json".to_string(), None);
+  assert_eq!(dirs, vec!["", "packages/a", "packages/b/nested/deep"]);
+}
+
+#[napi(object)]
+pub struct AncestorsEntry {
+  pub path: String,
+  /// Directories from the top-level one down to the file's parent,
+  /// empty for files located in the root directory
+  pub ancestors: Vec<String>,
+}
+
+/// Returns matched files along with their ancestor directories,
+/// e.g. for creating intermediate nodes of a tree.
+#[napi]
+pub fn walk_repo_glob_ancestors(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> Vec<AncestorsEntry> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return vec![];
+  };
+
+  walk_repo(&repo_dir, &options, |path, _| {
+    if !matcher.is_match(path) {
+      return None;
+    }
+
+    let ancestors = path
+      .match_indices('/')
+      .map(|(idx, _)| output_path(path[..idx].to_string(), &options))
+      .collect();
+
+    Some(AncestorsEntry {
+      path: output_path(path.to_string(), &options),
+      ancestors,
+    })
+  })
+}
+
+#[test]
+fn test_walk_repo_glob_ancestors() {
+  let fixture = make_fixture(&["package.json", "packages/a/src/package.json"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let entries = walk_repo_glob_ancestors(repo, "**/package.json".to_string(), None);
+  assert_eq!(entries.len(), 2);
+  assert_eq!(entries[0].path, "package.json");
+  assert!(entries[0].ancestors.is_empty());
+  assert_eq!(entries[1].path, "packages/a/src/package.json");
+  assert_eq!(
+    entries[1].ancestors,
+    vec!["packages", "packages/a", "packages/a/src"]
+  );
+}
+
+/// Returns unique directories containing matched files at any depth, i.e. all
+/// ancestors of matches, with the empty string standing for the root directory.
+#[napi]
+pub fn walk_repo_glob_dirs_with_matches(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> Vec<String> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return vec![];
+  };
+
+  let mut seen = HashSet::new();
+  let dirs = walk_repo(&repo_dir, &options, |path, _| {
+    if !matcher.is_match(path) {
+      return None;
+    }
+
+    let dirs: Vec<String> = std::iter::once("")
+      .chain(path.match_indices('/').map(|(idx, _)| &path[..idx]))
+      .map(str::to_string)
+      .filter(|dir| seen.insert(dir.clone()))
+      .collect();
+    Some(dirs)
+  });
+  finish_paths(dirs.into_iter().flatten().collect(), &options)
+}
+
+#[test]
+fn test_walk_repo_glob_dirs_with_matches() {
+  let fixture = make_fixture(&["a/b/c.json", "a/d.json", "e/f/g.txt", "h.json"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let dirs = walk_repo_glob_dirs_with_matches(repo, "**/*.json".to_string(), None);
+  assert_eq!(dirs, vec!["", "a", "a/b"]);
+}
+
+#[napi(object)]
+pub struct CaptureMatch {
+  pub path: String,
+  /// Part of the path matched by the captured glob segment, missing
+  /// when it would span several directories, e.g. `*` matching `a/b`
+  pub capture: Option<String>,
+}
+
+/// Stands for the captured segment while the whole glob is translated into a regex
+const CAPTURE_PLACEHOLDER: char = '\u{e000}';
+
+/// Regex of `glob` with the `/`-separated segment at `segment` turned into a capture group.
+/// Within the group, `*` doesn't match `/`, so the capture stays inside one segment.
+fn glob_capture_regex(
+  repo_dir: &str,
+  glob: &str,
+  segment: usize,
+  options: &WalkOptions,
+) -> Option<regex::bytes::Regex> {
+  let mut segments: Vec<&str> = glob.split('/').collect();
+  let captured = std::mem::take(segments.get_mut(segment)?);
+  let placeholder = CAPTURE_PLACEHOLDER.to_string();
+  segments[segment] = &placeholder;
+
+  let outer = compile_glob(repo_dir, &segments.join("/"), options)?;
+  let inner = globset::GlobBuilder::new(captured)
+    .literal_separator(true)
+    .build()
+    .ok()?;
+  let inner = inner.regex();
+  let inner = inner.strip_prefix("(?-u)^")?.strip_suffix('$')?;
+
+  let escaped_placeholder: String = placeholder
+    .bytes()
+    .map(|byte| format!("\\x{:02x}", byte))
+    .collect();
+  let regex = outer
+    .regex()
+    .replacen(&escaped_placeholder, &format!("({})", inner), 1);
+  regex::bytes::Regex::new(&regex).ok()
+}
+
+/// Returns matched files along with the part of their path matched by the glob
+/// segment at `captureSegment`, e.g. the package name for segment 1
+/// of `packages/*`, which helps to group results by package.
+#[napi]
+pub fn walk_repo_glob_captures(
+  repo_dir: String,
+  glob: String,
+  capture_segment: u32,
+  options: Option<WalkOptions>,
+) -> Vec<CaptureMatch> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return vec![];
+  };
+
+  let capture_regex = glob_capture_regex(&repo_dir, &glob, capture_segment as usize, &options);
+  walk_repo(&repo_dir, &options, |path, _| {
+    if !matcher.is_match(path) {
+      return None;
+    }
+
+    let capture = capture_regex.as_ref().and_then(|regex| {
+      let subject = matcher.subject(path);
+      let captures = regex.captures(subject.as_bytes())?;
+      let capture = std::str::from_utf8(captures.get(1)?.as_bytes()).ok()?;
+      Some(capture.to_string())
+    });
+
+    Some(CaptureMatch {
+      path: output_path(path.to_string(), &options),
+      capture,
+    })
+  })
+}
+
+#[test]
+fn test_walk_repo_glob_captures() {
+  let fixture = make_fixture(&[
+    "pkg-a/src/lib.rs",
+    "pkg-b/src/bin/main.rs",
+    "pkg-b/README.md",
+    "src/lib.rs",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let captures = |glob: &str, segment: u32| -> Vec<(String, Option<String>)> {
+    walk_repo_glob_captures(repo.clone(), glob.to_string(), segment, None)
+      .into_iter()
+      .map(|m| (m.path, m.capture))
+      .collect()
+  };
+  let owned = |path: &str, capture: &str| (path.to_string(), Some(capture.to_string()));
+
+  assert_eq!(
+    captures("*/src/**/*.rs", 0),
+    vec![
+      owned("pkg-a/src/lib.rs", "pkg-a"),
+      owned("pkg-b/src/bin/main.rs", "pkg-b"),
+    ]
+  );
+  assert_eq!(
+    captures("**/src/*.rs", 2),
+    vec![
+      owned("pkg-a/src/lib.rs", "lib.rs"),
+      ("pkg-b/src/bin/main.rs".to_string(), None),
+      owned("src/lib.rs", "lib.rs"),
+    ]
+  );
+  assert_eq!(
+    captures("pkg-{a,b}/**", 0),
+    vec![
+      owned("pkg-a/src/lib.rs", "pkg-a"),
+      owned("pkg-b/README.md", "pkg-b"),
+      owned("pkg-b/src/bin/main.rs", "pkg-b"),
+    ]
+  );
+  assert_eq!(
+    captures("src/*.rs", 5),
+    vec![("src/lib.rs".to_string(), None)]
+  );
+}
+
+#[napi(object)]
+pub struct DepthEntry {
+  pub path: String,
+  /// Number of path segments, i.e. 1 for files in the root directory
+  pub depth: u32,
+}
+
+/// Returns matched files along with their depth
+#[napi]
+pub fn walk_repo_glob_depths(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> Vec<DepthEntry> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return vec![];
+  };
+
+  walk_repo(&repo_dir, &options, |path, _| {
+    if !matcher.is_match(path) {
+      return None;
+    }
+
+    Some(DepthEntry {
+      path: output_path(path.to_string(), &options),
+      depth: path.matches('/').count() as u32 + 1,
+    })
+  })
+}
+
+#[test]
+fn test_walk_repo_glob_depths() {
+  let fixture = make_fixture(&["x.json", "a/b/c.json"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let entries = walk_repo_glob_depths(repo, "**/*.json".to_string(), None);
+  assert_eq!(entries.len(), 2);
+  assert_eq!((entries[0].path.as_str(), entries[0].depth), ("x.json", 1));
+  assert_eq!(
+    (entries[1].path.as_str(), entries[1].depth),
+    ("a/b/c.json", 3)
+  );
+}
+
+/// Groups matched files by their top-level directory,
+/// with the empty string key standing for files in the root directory.
+#[napi]
+pub fn walk_repo_glob_by_toplevel(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> HashMap<String, Vec<String>> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return HashMap::new();
+  };
+
+  let paths = walk_repo(&repo_dir, &options, |path, _| {
+    if matcher.is_match(path) {
+      Some(path.to_string())
+    } else {
+      None
+    }
+  });
+
+  let mut accum: HashMap<String, Vec<String>> = HashMap::new();
+  for path in paths {
+    let toplevel = path.split_once('/').map_or("", |(toplevel, _)| toplevel);
+    let toplevel = output_path(toplevel.to_string(), &options);
+    accum.entry(toplevel).or_default().push(path);
+  }
+
+  accum
+    .into_iter()
+    .map(|(key, paths)| (key, finish_paths(paths, &options)))
+    .collect()
+}
+
+#[test]
+fn test_walk_repo_glob_by_toplevel() {
+  let fixture = make_fixture(&[
+    "root.json",
+    "a/x.json",
+    "a/y.json",
+    "b/c/z.json",
+    "b/readme.md",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let groups = walk_repo_glob_by_toplevel(repo, "**/*.json".to_string(), None);
+  assert_eq!(groups.len(), 3);
+  assert_eq!(groups[""], vec!["root.json"]);
+  assert_eq!(groups["a"], vec!["a/x.json", "a/y.json"]);
+  assert_eq!(groups["b"], vec!["b/c/z.json"]);
+}
+
+#[napi(object)]
+pub struct TreeNode {
+  pub name: String,
+  /// Directories first, then files, each sorted by name
+  pub children: Vec<TreeNode>,
+  pub is_file: bool,
+}
+
+#[derive(Default)]
+struct DirTree {
+  dirs: BTreeMap<String, DirTree>,
+  files: BTreeSet<String>,
+}
+
+impl DirTree {
+  fn insert(&mut self, path: &str) {
+    match path.split_once('/') {
+      Some((dir, rest)) => self.dirs.entry(dir.to_string()).or_default().insert(rest),
+      None => {
+        self.files.insert(path.to_string());
+      }
+    }
+  }
+
+  fn into_nodes(self) -> Vec<TreeNode> {
+    let dirs = self.dirs.into_iter().map(|(name, dir)| TreeNode {
+      name,
+      children: dir.into_nodes(),
+      is_file: false,
+    });
+    let files = self.files.into_iter().map(|name| TreeNode {
+      name,
+      children: vec![],
+      is_file: true,
+    });
+    dirs.chain(files).collect()
+  }
+}
+
+/// Returns matched files as a tree of the top-level entries, ready to render.
+/// Directories are only included when they contain matches.
+#[napi]
+pub fn walk_repo_glob_tree(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> Vec<TreeNode> {
+  // Nodes hold names rather than paths, so separators never show up in the result
+  let options = WalkOptions {
+    native_separators: None,
+    ..options.unwrap_or_default()
+  };
+  let mut tree = DirTree::default();
+  for path in walk_repo_glob(repo_dir, glob, Some(options)) {
+    tree.insert(&path);
+  }
+  tree.into_nodes()
+}
+
+#[test]
+fn test_walk_repo_glob_tree() {
+  let fixture = make_fixture(&[
+    "Cargo.toml",
+    "src/lib.rs",
+    "src/bin/main.rs",
+    "src/README.md",
+    "docs/index.md",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+
+  fn render(nodes: &[TreeNode]) -> Vec<String> {
+    nodes
+      .iter()
+      .map(|node| {
+        if node.is_file {
+          node.name.clone()
+        } else {
+          format!("{}/[{}]", node.name, render(&node.children).join(", "))
+        }
+      })
+      .collect()
+  }
+
+  let tree = walk_repo_glob_tree(repo.clone(), "src/lib.rs".to_string(), None);
+  assert_eq!(render(&tree), vec!["src/[lib.rs]"]);
+
+  let tree = walk_repo_glob_tree(repo, "**/*.{rs,toml}".to_string(), None);
+  assert_eq!(
+    render(&tree),
+    vec!["src/[bin/[main.rs], lib.rs]", "Cargo.toml"]
+  );
+}
+
+#[cfg(windows)]
+#[test]
+fn test_walk_repo_glob_tree_native_separators() {
+  let fixture = make_fixture(&["src\\lib.rs", "src\\bin\\main.rs"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let options = Some(WalkOptions {
+    native_separators: Some(true),
+    ..Default::default()
+  });
+  let tree = walk_repo_glob_tree(repo, "src/**/*.rs".to_string(), options);
+  assert_eq!(tree.len(), 1);
+  assert_eq!(tree[0].name, "src");
+  let names: Vec<&str> = tree[0]
+    .children
+    .iter()
+    .map(|node| node.name.as_str())
+    .collect();
+  assert_eq!(names, vec!["bin", "lib.rs"]);
+}
+
+#[napi(object)]
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileEntry {
+  pub path: String,
+  pub size: f64,
+  pub mtime_ms: f64,
+}
+
+impl FileEntry {
+  fn new(path: &str, entry: &ignore::DirEntry) -> Option<FileEntry> {
+    let metadata = entry.metadata().ok()?;
+    let mtime_ms = metadata
+      .modified()
+      .ok()
+      .and_then(|mtime| mtime.duration_since(UNIX_EPOCH).ok())
+      .map_or(0.0, |mtime| mtime.as_secs_f64() * 1000.0);
+
+    Some(FileEntry {
+      path: path.to_string(),
+      size: metadata.len() as f64,
+      mtime_ms,
+    })
+  }
+}
+
+fn walk_repo_glob_entries(repo_dir: &str, glob: &str, options: &WalkOptions) -> Vec<FileEntry> {
+  let Some(matcher) = GlobMatcher::single(repo_dir, glob, options) else {
+    return vec![];
+  };
+
+  walk_repo(repo_dir, options, |path, entry| {
+    if matcher.is_match(path) {
+      FileEntry::new(path, entry)
+    } else {
+      None
+    }
+  })
+}
+
+#[test]
+fn test_walk_repo_glob_entries() {
+  let fixture = make_fixture(&["a.json", "b.txt"]);
+  std::fs::write(fixture.path().join("a.json"), "{}").unwrap();
+  let repo = fixture.path().to_str().unwrap();
+  let entries = walk_repo_glob_entries(repo, "*.json", &WalkOptions::default());
+  assert_eq!(entries.len(), 1);
+  assert_eq!(entries[0].path, "a.json");
+  assert_eq!(entries[0].size, 2.0);
+  assert!(entries[0].mtime_ms > 0.0);
+}
+
+/// Returns the most recently modified matched file of every directory,
+/// with ties broken by the lexicographically smallest path.
+#[napi]
+pub fn walk_repo_glob_newest_per_dir(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> Vec<FileEntry> {
+  let options = options.unwrap_or_default();
+  let entries = walk_repo_glob_entries(&repo_dir, &glob, &options);
+
+  let mut newest: Vec<FileEntry> = vec![];
+  let mut indices: HashMap<String, usize> = HashMap::new();
+  for entry in entries {
+    match indices.entry(parent_dir(&entry.path).to_string()) {
+      Entry::Vacant(vacant) => {
+        vacant.insert(newest.len());
+        newest.push(entry);
+      }
+      Entry::Occupied(occupied) => {
+        let current = &mut newest[*occupied.get()];
+        let is_newer = entry.mtime_ms > current.mtime_ms
+          || (entry.mtime_ms == current.mtime_ms && entry.path < current.path);
+        if is_newer {
+          *current = entry;
+        }
+      }
+    }
+  }
+
+  newest
+    .into_iter()
+    .map(|entry| FileEntry {
+      path: output_path(entry.path, &options),
+      ..entry
+    })
+    .collect()
+}
+
+#[test]
+fn test_walk_repo_glob_newest_per_dir() {
+  let fixture = make_fixture(&[
+    "logs/a.log",
+    "logs/b.log",
+    "logs/c.log",
+    "other/x.log",
+    "root.log",
+  ]);
+  let set_mtime = |file: &str, secs: u64| {
+    std::fs::File::options()
+      .write(true)
+      .open(fixture.path().join(file))
+      .unwrap()
+      .set_modified(UNIX_EPOCH + std::time::Duration::from_secs(secs))
+      .unwrap();
+  };
+  set_mtime("logs/a.log", 1000);
+  set_mtime("logs/b.log", 3000);
+  set_mtime("logs/c.log", 3000);
+  set_mtime("other/x.log", 2000);
+  set_mtime("root.log", 500);
+
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let newest = walk_repo_glob_newest_per_dir(repo, "**/*.log".to_string(), None);
+  let paths: Vec<&str> = newest.iter().map(|entry| entry.path.as_str()).collect();
+  assert_eq!(paths, vec!["root.log", "logs/b.log", "other/x.log"]);
+  assert_eq!(newest[1].mtime_ms, 3_000_000.0);
+}
+
+/// Writes `{ path, size, mtimeMs }` of every matched file to `outPath` as JSON lines
+/// while walking, so memory use doesn't grow with the number of matches.
+/// `outPath` is truncated first, even when nothing matches or `glob` is invalid.
+/// Returns the number of lines written.
+#[napi]
+pub fn walk_repo_glob_to_file(
+  repo_dir: String,
+  glob: String,
+  out_path: String,
+  options: Option<WalkOptions>,
+) -> napi::Result<u32, ScanError> {
+  let to_scan_error = |err: std::io::Error| {
+    let reason = format!("Failed to write {}: {}", out_path, err);
+    scan_error(ScanError::IoError, reason)
+  };
+
+  // Truncated even when nothing matches, so no lines of earlier runs are left
+  let file = std::fs::File::create(&out_path).map_err(to_scan_error)?;
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return Ok(0);
+  };
+
+  let mut writer = BufWriter::new(file);
+  let mut count = 0;
+  let mut error: Option<std::io::Error> = None;
+  walk_repo(&repo_dir, &options, |path, entry| {
+    if error.is_some() || !matcher.is_match(path) {
+      return None;
+    }
+
+    let file_entry = FileEntry::new(path, entry)?;
+    let file_entry = FileEntry {
+      path: output_path(file_entry.path, &options),
+      ..file_entry
+    };
+    let written = serde_json::to_writer(&mut writer, &file_entry)
+      .map_err(std::io::Error::from)
+      .and_then(|_| writer.write_all(b"\n"));
+    match written {
+      Ok(_) => count += 1,
+      Err(err) => error = Some(err),
+    }
+
+    None::<()>
+  });
+
+  if let Some(err) = error {
+    return Err(to_scan_error(err));
+  }
+
+  writer.flush().map_err(to_scan_error)?;
+  Ok(count)
+}
+
+#[test]
+fn test_walk_repo_glob_to_file() {
+  let fixture = make_fixture(&["a.json", "b/c.json", "d.txt"]);
+  let out = tempfile::tempdir().unwrap();
+  let out_path = out.path().join("out.jsonl");
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let count = walk_repo_glob_to_file(
+    repo,
+    "**/*.json".to_string(),
+    out_path.to_str().unwrap().to_string(),
+    None,
+  )
+  .unwrap();
+  assert_eq!(count, 2);
+
+  let content = std::fs::read_to_string(out_path).unwrap();
+  let lines: Vec<serde_json::Value> = content
+    .lines()
+    .map(|line| serde_json::from_str(line).unwrap())
+    .collect();
+  assert_eq!(lines.len(), 2);
+  assert_eq!(lines[0]["path"], "a.json");
+  assert_eq!(lines[1]["path"], "b/c.json");
+  assert_eq!(lines[1]["size"], 0.0);
+  assert!(lines[1]["mtimeMs"].as_f64().unwrap() > 0.0);
+}
+
+#[test]
+fn test_walk_repo_glob_to_file_truncates() {
+  let fixture = make_fixture(&["a.json"]);
+  let out = tempfile::tempdir().unwrap();
+  let out_path = out.path().join("out.jsonl");
+  std::fs::write(&out_path, "{\"path\":\"stale.json\"}\n").unwrap();
+
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let out_path_str = out_path.to_str().unwrap().to_string();
+  let count = walk_repo_glob_to_file(repo, "[*.json".to_string(), out_path_str, None).unwrap();
+  assert_eq!(count, 0);
+  assert_eq!(std::fs::read_to_string(out_path).unwrap(), "");
+}
+
+const DEFAULT_STREAM_BUFFER_SIZE: usize = 256;
+
+/// Calls `callback` with every matched path as soon as it's found, and once more
+/// with `null` when the walk is done. The walk runs on a separate thread and pauses
+/// while `bufferSize` paths (256 by default) are waiting to be passed to `callback`.
+/// Paths come in walk order, so `uniqueSorted` and `naturalSort` have no effect.
+#[napi(
+  ts_args_type = "repoDir: string, glob: string, callback: (err: Error | null, path: string | null) => void, bufferSize?: number | undefined | null, options?: WalkOptions | undefined | null"
+)]
+pub fn walk_repo_glob_stream(
+  repo_dir: String,
+  glob: String,
+  callback: JsFunction,
+  buffer_size: Option<u32>,
+  options: Option<WalkOptions>,
+) -> napi::Result<()> {
+  // Queue size of 1 keeps the channel the only place where paths pile up
+  let callback: ThreadsafeFunction<Option<String>> = callback
+    .create_threadsafe_function(1, |ctx: ThreadSafeCallContext<Option<String>>| {
+      Ok(vec![ctx.value])
+    })?;
+
+  let buffer_size = buffer_size.map_or(DEFAULT_STREAM_BUFFER_SIZE, |size| size as usize);
+  let receiver = spawn_glob_walk(repo_dir, glob, options.unwrap_or_default(), buffer_size);
+  std::thread::spawn(move || {
+    for path in receiver {
+      callback.call(Ok(Some(path)), ThreadsafeFunctionCallMode::Blocking);
+    }
+    callback.call(Ok(None), ThreadsafeFunctionCallMode::Blocking);
+  });
+  Ok(())
+}
+
+/// Walks on a separate thread, sending matched paths through a channel
+/// which holds at most `buffer_size` of them. The walk stops sending
+/// once the receiver is dropped.
+fn spawn_glob_walk(
+  repo_dir: String,
+  glob: String,
+  options: WalkOptions,
+  buffer_size: usize,
+) -> Receiver<String> {
+  let (sender, receiver) = sync_channel(buffer_size);
+  std::thread::spawn(move || {
+    let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+      return;
+    };
+
+    let mut disconnected = false;
+    walk_repo(&repo_dir, &options, |path, _| {
+      if !disconnected && matcher.is_match(path) {
+        disconnected = sender
+          .send(output_path(path.to_string(), &options))
+          .is_err();
+      }
+      None::<()>
+    });
+  });
+  receiver
+}
+
+#[test]
+fn test_spawn_glob_walk_small_buffer() {
+  let files: Vec<String> = (0..20)
+    .map(|idx| format!("dir{}/file{}.json", idx % 4, idx))
+    .collect();
+  let fixture = make_fixture(&files.iter().map(String::as_str).collect::<Vec<_>>());
+  let repo = fixture.path().to_str().unwrap().to_string();
+
+  let receiver = spawn_glob_walk(
+    repo.clone(),
+    "**/*.json".to_string(),
+    WalkOptions::default(),
+    1,
+  );
+  let mut streamed = vec![];
+  for path in receiver {
+    std::thread::sleep(std::time::Duration::from_millis(1));
+    streamed.push(path);
+  }
+  assert_eq!(streamed.len(), 20);
+  assert_eq!(
+    streamed,
+    walk_repo_glob(repo, "**/*.json".to_string(), None)
+  );
+}
+
+const DEFAULT_STREAM_BATCH_SIZE: usize = 64;
+
+/// Like `walkRepoGlobStream`, but calls `callback` with arrays of up to `batchSize`
+/// paths (64 by default) to cut down on calls into JS. A batch is passed on as soon
+/// as the walk has no more paths ready, so it can be shorter than `batchSize`.
+#[napi(
+  ts_args_type = "repoDir: string, glob: string, callback: (err: Error | null, paths: string[] | null) => void, batchSize?: number | undefined | null, bufferSize?: number | undefined | null, options?: WalkOptions | undefined | null"
+)]
+pub fn walk_repo_glob_stream_batched(
+  repo_dir: String,
+  glob: String,
+  callback: JsFunction,
+  batch_size: Option<u32>,
+  buffer_size: Option<u32>,
+  options: Option<WalkOptions>,
+) -> napi::Result<()> {
+  let callback: ThreadsafeFunction<Option<Vec<String>>> = callback
+    .create_threadsafe_function(1, |ctx: ThreadSafeCallContext<Option<Vec<String>>>| {
+      Ok(vec![ctx.value])
+    })?;
+
+  let batch_size = batch_size.map_or(DEFAULT_STREAM_BATCH_SIZE, |size| size.max(1) as usize);
+  let buffer_size = buffer_size.map_or(DEFAULT_STREAM_BUFFER_SIZE, |size| size as usize);
+  let receiver = spawn_glob_walk(repo_dir, glob, options.unwrap_or_default(), buffer_size);
+  std::thread::spawn(move || {
+    while let Some(batch) = recv_batch(&receiver, batch_size) {
+      callback.call(Ok(Some(batch)), ThreadsafeFunctionCallMode::Blocking);
+    }
+    callback.call(Ok(None), ThreadsafeFunctionCallMode::Blocking);
+  });
+  Ok(())
+}
+
+/// Waits for the next path, then takes up to `batch_size - 1` more that are
+/// already waiting. Returns `None` once the walk is done.
+fn recv_batch(receiver: &Receiver<String>, batch_size: usize) -> Option<Vec<String>> {
+  let mut batch = vec![receiver.recv().ok()?];
+  batch.extend(receiver.try_iter().take(batch_size - 1));
+  Some(batch)
+}
+
+#[test]
+fn test_recv_batch() {
+  let files: Vec<String> = (0..50)
+    .map(|idx| format!("dir{}/file{}.json", idx % 4, idx))
+    .collect();
+  let fixture = make_fixture(&files.iter().map(String::as_str).collect::<Vec<_>>());
+  let repo = fixture.path().to_str().unwrap().to_string();
+
+  let receiver = spawn_glob_walk(
+    repo.clone(),
+    "**/*.json".to_string(),
+    WalkOptions::default(),
+    4,
+  );
+  let mut streamed = vec![];
+  while let Some(batch) = recv_batch(&receiver, 8) {
+    assert!(!batch.is_empty() && batch.len() <= 8);
+    streamed.extend(batch);
+  }
+  assert_eq!(streamed.len(), 50);
+  assert_eq!(
+    streamed,
+    walk_repo_glob(repo, "**/*.json".to_string(), None)
+  );
+}
+
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct ScanOptions {
+  /// Files matching any of these globs are returned
+  pub globs: Vec<String>,
+  /// Files matching any of these globs are left out, even if matched by `globs`
+  pub excludes: Option<Vec<String>>,
+  /// Number of visited files between progress calls, 1000 by default
+  pub progress_interval: Option<u32>,
+  pub walk: Option<WalkOptions>,
+}
+
+#[napi(object)]
+pub struct ScanProgress {
+  /// Files visited so far, matched or not
+  pub visited: u32,
+  pub matched: u32,
+}
+
+#[napi(object)]
+pub struct ScanResult {
+  pub paths: Vec<String>,
+  pub visited: u32,
+  /// Whether the scan was aborted, in which case `paths` holds the matches found until then
+  pub cancelled: bool,
+}
+
+const DEFAULT_PROGRESS_INTERVAL: u32 = 1000;
+
+/// Walks on the libuv thread pool and resolves with files matching `globs` but not `excludes`.
+///
+/// Throws `RepoNotFound` when `repoDir` doesn't exist and `InvalidGlob` for any invalid glob,
+/// before the walk starts.
+///
+/// `progress` is called with counts every `progressInterval` visited files. Calls are
+/// skipped while the previous one is still waiting for the main thread, so they don't
+/// slow the walk down.
+///
+/// Aborting `signal` stops the walk at the next file, and the promise resolves with the
+/// matches found so far and `cancelled` set, rather than rejecting. No progress calls are
+/// made after the abort, but one made just before it may still arrive afterwards.
+#[napi(
+  ts_args_type = "repoDir: string, options: ScanOptions, progress?: ((progress: ScanProgress) => void) | undefined | null, signal?: AbortSignal | undefined | null",
+  ts_return_type = "Promise<ScanResult>"
+)]
+pub fn scan(
+  env: Env,
+  repo_dir: String,
+  options: ScanOptions,
+  progress: Option<JsFunction>,
+  signal: Option<JsObject>,
+) -> napi::Result<AsyncTask<ScanTask>, ScanError> {
+  check_repo_dir(&repo_dir)?;
+  let walk_options = options.walk.clone().unwrap_or_default();
+  check_gitmodules(&repo_dir, &walk_options)?;
+  for glob in options
+    .globs
+    .iter()
+    .chain(options.excludes.iter().flatten())
+  {
+    if compile_glob(&repo_dir, glob, &walk_options).is_none() {
+      check_globs(&[glob])?;
+      let reason = format!("Unsupported glob: {}", glob);
+      return Err(scan_error(ScanError::InvalidGlob, reason));
+    }
+  }
+
+  let to_scan_error = |err: napi::Error| scan_error(ScanError::IoError, err.reason);
+  let progress = progress
+    .map(|callback| {
+      callback.create_threadsafe_function(1, |ctx: ThreadSafeCallContext<ScanProgress>| {
+        Ok(vec![ctx.value])
+      })
+    })
+    .transpose()
+    .map_err(to_scan_error)?;
+
+  let cancelled = Arc::new(AtomicBool::new(false));
+  if let Some(signal) = signal {
+    listen_for_abort(&env, &signal, cancelled.clone()).map_err(to_scan_error)?;
+  }
+
+  Ok(AsyncTask::new(ScanTask {
+    repo_dir,
+    options,
+    progress,
+    cancelled,
+  }))
+}
+
+/// Sets `cancelled` once `signal` is aborted, or right away if it already is
+fn listen_for_abort(env: &Env, signal: &JsObject, cancelled: Arc<AtomicBool>) -> napi::Result<()> {
+  if signal.get_named_property::<bool>("aborted")? {
+    cancelled.store(true, Ordering::Relaxed);
+    return Ok(());
+  }
+
+  let listener = env.create_function_from_closure("onabort", move |_| {
+    cancelled.store(true, Ordering::Relaxed);
+    Ok(())
+  })?;
+  let add_event_listener: JsFunction = signal.get_named_property("addEventListener")?;
+  let event = env.create_string("abort")?.into_unknown();
+  add_event_listener.call(Some(signal), &[event, listener.into_unknown()])?;
+  Ok(())
+}
+
+pub struct ScanTask {
+  repo_dir: String,
+  options: ScanOptions,
+  progress: Option<ThreadsafeFunction<ScanProgress, ErrorStrategy::Fatal>>,
+  cancelled: Arc<AtomicBool>,
+}
+
+impl Task for ScanTask {
+  type Output = ScanResult;
+  type JsValue = ScanResult;
+
+  fn compute(&mut self) -> napi::Result<ScanResult> {
+    Ok(run_scan(
+      &self.repo_dir,
+      &self.options,
+      &self.cancelled,
+      |progress| {
+        if let Some(callback) = &self.progress {
+          callback.call(progress, ThreadsafeFunctionCallMode::NonBlocking);
+        }
+      },
+    ))
+  }
+
+  fn resolve(&mut self, _env: Env, output: ScanResult) -> napi::Result<ScanResult> {
+    Ok(output)
+  }
+}
+
+/// Walks until done or until `cancelled` is set, which is checked before every file
+fn run_scan<P>(
+  repo_dir: &str,
+  options: &ScanOptions,
+  cancelled: &AtomicBool,
+  mut on_progress: P,
+) -> ScanResult
+where
+  P: FnMut(ScanProgress),
+{
+  let walk_options = options.walk.clone().unwrap_or_default();
+  let excludes = options.excludes.as_deref().unwrap_or_default();
+  let (Some(include), Some(exclude)) = (
+    GlobMatcher::new(repo_dir, &options.globs, &walk_options),
+    GlobMatcher::new(repo_dir, excludes, &walk_options),
+  ) else {
+    return ScanResult {
+      paths: vec![],
+      visited: 0,
+      cancelled: false,
+    };
+  };
+
+  let interval = options
+    .progress_interval
+    .map_or(DEFAULT_PROGRESS_INTERVAL, |interval| interval.max(1));
+  let layout = RepoLayout::load(repo_dir, &walk_options);
+  // Unmatched files are yielded as well, so that the walk can be stopped between any files
+  let files = walk_repo_iter(repo_dir, None, &walk_options, &layout, |path, _| {
+    let is_match = include.is_match(path) && !exclude.is_match(path);
+    Some(is_match.then(|| path.to_string()))
+  });
+
+  let mut paths = vec![];
+  let mut visited = 0;
+  let mut was_cancelled = false;
+  for path in files {
+    if cancelled.load(Ordering::Relaxed) {
+      was_cancelled = true;
+      break;
+    }
+
+    visited += 1;
+    paths.extend(path);
+    if visited % interval == 0 {
+      on_progress(ScanProgress {
+        visited,
+        matched: paths.len() as u32,
+      });
+    }
+  }
+
+  ScanResult {
+    paths: finish_paths(paths, &walk_options),
+    visited,
+    cancelled: was_cancelled,
+  }
+}
+
+#[test]
+fn test_run_scan() {
+  let files: Vec<String> = (0..40)
+    .map(|idx| {
+      let ext = if idx % 3 == 0 { "txt" } else { "json" };
+      format!("dir{}/file{}.{}", idx % 4, idx, ext)
+    })
+    .collect();
+  let fixture = make_fixture(&files.iter().map(String::as_str).collect::<Vec<_>>());
+  let repo = fixture.path().to_str().unwrap();
+  let options = ScanOptions {
+    globs: vec!["**/*.json".to_string()],
+    excludes: Some(vec!["dir2/**".to_string()]),
+    progress_interval: Some(10),
+    ..Default::default()
+  };
+
+  let mut progress = vec![];
+  let result = run_scan(repo, &options, &AtomicBool::new(false), |p| {
+    progress.push((p.visited, p.matched))
+  });
+  assert!(!result.cancelled);
+  assert_eq!(result.visited, 40);
+  assert_eq!(result.paths.len(), 19);
+  assert!(result.paths.iter().all(|path| !path.starts_with("dir2/")));
+  let visited: Vec<u32> = progress.iter().map(|(visited, _)| *visited).collect();
+  assert_eq!(visited, vec![10, 20, 30, 40]);
+  assert_eq!(progress.last().unwrap().1, 19);
+
+  // Cancelled from the progress callback after the first 10 files
+  let cancelled = AtomicBool::new(false);
+  let partial = run_scan(repo, &options, &cancelled, |_| {
+    cancelled.store(true, Ordering::Relaxed)
+  });
+  assert!(partial.cancelled);
+  assert_eq!(partial.visited, 10);
+  assert!(partial.paths.len() < result.paths.len());
+  assert_eq!(partial.paths, result.paths[..partial.paths.len()]);
+}
+
+/// Returns a hex digest of the sorted matched paths, which stays the same until
+/// a matched file is added or removed. With `includeMtimes`, modification times
+/// are hashed as well, so that modified files change the digest too.
+#[napi]
+pub fn fingerprint_repo_glob(
+  repo_dir: String,
+  glob: String,
+  include_mtimes: Option<bool>,
+  options: Option<WalkOptions>,
+) -> String {
+  let options = options.unwrap_or_default();
+  let mut entries = walk_repo_glob_entries(&repo_dir, &glob, &options);
+  entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+  let mut data = vec![];
+  for entry in &entries {
+    data.extend_from_slice(entry.path.as_bytes());
+    if include_mtimes == Some(true) {
+      data.push(0);
+      data.extend_from_slice(entry.mtime_ms.to_string().as_bytes());
+    }
+    data.push(b'\n');
+  }
+
+  gix::objs::compute_hash(gix::hash::Kind::Sha1, gix::objs::Kind::Blob, &data).to_string()
+}
+
+#[test]
+fn test_fingerprint_repo_glob() {
+  let first = fingerprint_repo_glob(".".to_string(), "**/*.rs".to_string(), Some(true), None);
+  let second = fingerprint_repo_glob(".".to_string(), "**/*.rs".to_string(), Some(true), None);
+  assert_eq!(first, second);
+  assert_eq!(first.len(), 40);
+
+  let fixture = make_fixture(&["a.json", "b.json"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let fingerprint = || fingerprint_repo_glob(repo.clone(), "*.json".to_string(), Some(true), None);
+  let initial = fingerprint();
+
+  std::fs::write(fixture.path().join("c.json"), "{}").unwrap();
+  let added = fingerprint();
+  assert_ne!(added, initial);
+
+  std::fs::remove_file(fixture.path().join("c.json")).unwrap();
+  assert_eq!(fingerprint(), initial);
+
+  let file = std::fs::File::options()
+    .write(true)
+    .open(fixture.path().join("a.json"))
+    .unwrap();
+  file
+    .set_modified(UNIX_EPOCH + std::time::Duration::from_secs(1000))
+    .unwrap();
+  assert_ne!(fingerprint(), initial);
+}
+
+#[napi(object)]
+pub struct LinkEntry {
+  pub path: String,
+  /// Target of the symlink as written, unset for regular files
+  pub link_target: Option<String>,
+}
+
+/// Returns matched files along with symlink targets,
+/// which requires the `includeSymlinks` option to find any.
+#[napi]
+pub fn walk_repo_glob_link_targets(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> Vec<LinkEntry> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return vec![];
+  };
+
+  walk_repo(&repo_dir, &options, |path, entry| {
+    if !matcher.is_match(path) {
+      return None;
+    }
+
+    let link_target = if entry.path_is_symlink() {
+      let target = std::fs::read_link(entry.path()).ok()?;
+      Some(to_slash_path(&target)?.into_owned())
+    } else {
+      None
+    };
+
+    Some(LinkEntry {
+      path: output_path(path.to_string(), &options),
+      link_target,
+    })
+  })
+}
+
+#[cfg(unix)]
+#[test]
+fn test_walk_repo_glob_link_targets() {
+  let fixture = make_fixture(&["config/base.json", "lib/util.json"]);
+  std::os::unix::fs::symlink("config/base.json", fixture.path().join("link.json")).unwrap();
+  std::os::unix::fs::symlink("../lib", fixture.path().join("config/lib")).unwrap();
+  let repo = fixture.path().to_str().unwrap().to_string();
+
+  let entries = walk_repo_glob_link_targets(repo.clone(), "**/*".to_string(), None);
+  let paths: Vec<&str> = entries.iter().map(|entry| entry.path.as_str()).collect();
+  assert_eq!(paths, vec!["config/base.json", "lib/util.json"]);
+
+  let options = Some(WalkOptions {
+    include_symlinks: Some(true),
+    ..Default::default()
+  });
+  let entries = walk_repo_glob_link_targets(repo, "**/*".to_string(), options);
+  let entries: Vec<(&str, Option<&str>)> = entries
+    .iter()
+    .map(|entry| (entry.path.as_str(), entry.link_target.as_deref()))
+    .collect();
+  assert_eq!(
+    entries,
+    vec![
+      ("link.json", Some("config/base.json")),
+      ("config/base.json", None),
+      ("config/lib", Some("../lib")),
+      ("lib/util.json", None),
+    ]
+  );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_walk_repo_glob_symlinked_repo_dir() {
+  let fixture = make_fixture(&["a.json", "packages/b/c.json", "vendor/dep/d.json"]);
+  std::fs::write(
+    fixture.path().join(".gitmodules"),
+    "[submodule \"dep\"]\n  path = vendor/dep\n",
+  )
+  .unwrap();
+  gix::init(fixture.path()).unwrap();
+  let links = tempfile::tempdir().unwrap();
+  let link = links.path().join("repo");
+  std::os::unix::fs::symlink(fixture.path(), &link).unwrap();
+
+  // Entries are reported under the given path, so no canonicalization is needed
+  let repo = link.to_str().unwrap().to_string();
+  let paths = walk_repo_glob(repo.clone(), "**/*.json".to_string(), None);
+  assert_eq!(paths, vec!["a.json", "packages/b/c.json"]);
+
+  let paths = walk_repo_glob(format!("{}/", repo), "**/*.json".to_string(), None);
+  assert_eq!(paths, vec!["a.json", "packages/b/c.json"]);
+
+  let options = Some(WalkOptions {
+    repo_root_relative: Some(true),
+    ..Default::default()
+  });
+  let subdir = link.join("packages/b").to_str().unwrap().to_string();
+  let paths = walk_repo_glob(subdir, "**/*.json".to_string(), options);
+  assert_eq!(paths, vec!["packages/b/c.json"]);
+}
+
+/// Calls `mapper` with `{ path, size, mtimeMs }` of every matched file
+/// and collects its non-null results.
+///
+/// The walk itself completes before the first call, as JS can only be called
+/// from the main thread. Every call crosses the N-API boundary, which is
+/// noticeably slower than the specialized walk functions for large repos.
+#[napi(
+  ts_args_type = "repoDir: string, glob: string, mapper: (entry: FileEntry) => unknown, options?: WalkOptions | undefined | null",
+  ts_return_type = "Array<unknown>"
+)]
+pub fn walk_repo_glob_map_js(
+  env: Env,
+  repo_dir: String,
+  glob: String,
+  mapper: JsFunction,
+  options: Option<WalkOptions>,
+) -> napi::Result<JsObject> {
+  let options = options.unwrap_or_default();
+  let entries = walk_repo_glob_entries(&repo_dir, &glob, &options);
+
+  let mut result = env.create_empty_array()?;
+  let mut len = 0;
+  for entry in entries {
+    let mut arg = env.create_object()?;
+    arg.set("path", output_path(entry.path, &options))?;
+    arg.set("size", entry.size)?;
+    arg.set("mtimeMs", entry.mtime_ms)?;
+
+    let value = mapper.call(None, &[arg])?;
+    if matches!(value.get_type()?, ValueType::Null | ValueType::Undefined) {
+      continue;
+    }
+
+    result.set_element(len, value)?;
+    len += 1;
+  }
+
+  Ok(result)
+}
+
+enum SortKey {
+  Number(f64),
+  String(String),
+}
+
+impl SortKey {
+  /// Numbers precede strings, so that mixed keys still sort deterministically
+  fn cmp(&self, other: &SortKey) -> std::cmp::Ordering {
+    match (self, other) {
+      (SortKey::Number(a), SortKey::Number(b)) => a.total_cmp(b),
+      (SortKey::String(a), SortKey::String(b)) => a.cmp(b),
+      (SortKey::Number(_), SortKey::String(_)) => std::cmp::Ordering::Less,
+      (SortKey::String(_), SortKey::Number(_)) => std::cmp::Ordering::Greater,
+    }
+  }
+}
+
+/// Sorts `paths` by keys computed once per path. Paths with equal keys keep their order.
+fn sort_paths_by_key<F>(paths: Vec<String>, mut f: F) -> napi::Result<Vec<String>>
+where
+  F: FnMut(&str) -> napi::Result<SortKey>,
+{
+  let mut keyed = paths
+    .into_iter()
+    .map(|path| Ok((f(&path)?, path)))
+    .collect::<napi::Result<Vec<_>>>()?;
+  keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+  Ok(keyed.into_iter().map(|(_, path)| path).collect())
+}
+
+#[test]
+fn test_sort_paths_by_key() {
+  let paths: Vec<String> = vec!["src/index.ts", "a.md", "lib/b.js", "docs/readme.md"]
+    .into_iter()
+    .map(String::from)
+    .collect();
+  let basename_len = |path: &str| {
+    let len = path.rsplit('/').next().unwrap_or(path).len();
+    Ok(SortKey::Number(len as f64))
+  };
+  let sorted = sort_paths_by_key(paths.clone(), basename_len).unwrap();
+  assert_eq!(
+    sorted,
+    vec!["a.md", "lib/b.js", "src/index.ts", "docs/readme.md"]
+  );
+
+  let mixed = |path: &str| {
+    if path.ends_with(".md") {
+      Ok(SortKey::String(path.to_string()))
+    } else {
+      Ok(SortKey::Number(0.0))
+    }
+  };
+  let sorted = sort_paths_by_key(paths, mixed).unwrap();
+  assert_eq!(
+    sorted,
+    vec!["src/index.ts", "lib/b.js", "a.md", "docs/readme.md"]
+  );
+}
+
+/// Returns matched files ordered by keys computed with `sortKey`, which is called
+/// once per file rather than per comparison. Numeric keys precede string keys,
+/// and files with equal keys keep the walk order.
+#[napi(
+  ts_args_type = "repoDir: string, glob: string, sortKey: (path: string) => number | string, options?: WalkOptions | undefined | null"
+)]
+pub fn walk_repo_glob_sorted_by(
+  env: Env,
+  repo_dir: String,
+  glob: String,
+  sort_key: JsFunction,
+  options: Option<WalkOptions>,
+) -> napi::Result<Vec<String>> {
+  let paths = walk_repo_glob(repo_dir, glob, options);
+  sort_paths_by_key(paths, |path| {
+    let key = sort_key.call(None, &[env.create_string(path)?])?;
+    match key.get_type()? {
+      ValueType::Number => Ok(SortKey::Number(key.coerce_to_number()?.get_double()?)),
+      ValueType::String => Ok(SortKey::String(
+        key.coerce_to_string()?.into_utf8()?.into_owned()?,
+      )),
+      _ => Err(napi::Error::new(
+        napi::Status::InvalidArg,
+        format!("Sort key for {} must be a number or a string", path),
+      )),
+    }
+  })
+}
+
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct ReadOptions {
+  /// Total number of bytes to read across all files. Once exhausted,
+  /// reading stops and the matches found so far are returned.
+  pub max_total_bytes: Option<i64>,
+  /// Number of files read concurrently, each by its own thread. Defaults to 1,
+  /// i.e. sequential reads. Files failing to open, e.g. because of exceeding
+  /// the OS limit of open files, are treated as non-matching.
+  pub max_open_files: Option<u32>,
+  /// Files larger than this many bytes aren't read at all
+  pub max_size: Option<i64>,
+  /// Only the first this many bytes of each file are read, e.g. to look for
+  /// a header without loading whole bundles. Only these count towards `maxTotalBytes`.
+  pub max_read_bytes: Option<i64>,
+}
+
+struct ReadCandidate {
+  path: String,
+  full_path: PathBuf,
+  size: u64,
+}
+
+fn read_candidate(path: &str, entry: &ignore::DirEntry) -> ReadCandidate {
+  ReadCandidate {
+    path: path.to_string(),
+    full_path: entry.path().to_path_buf(),
+    size: entry.metadata().map_or(u64::MAX, |metadata| metadata.len()),
+  }
+}
+
+/// Calls `f` with contents of every candidate, returning results in the walk order.
+/// The `maxTotalBytes` budget is reserved in the walk order as well, so reads
+/// stop at the same file regardless of `maxOpenFiles`.
+fn read_files<T, F>(candidates: &[ReadCandidate], read_options: &ReadOptions, f: F) -> Vec<T>
+where
+  T: Send,
+  F: Fn(&ReadCandidate, &[u8]) -> Option<T> + Sync,
+{
+  let max_open_files = read_options.max_open_files.unwrap_or(1).max(1) as usize;
+  let max_size = read_options.max_size.map(|max| max.max(0) as u64);
+  let max_read_bytes = read_options
+    .max_read_bytes
+    .map_or(u64::MAX, |max| max.max(0) as u64);
+  let is_limited = read_options.max_total_bytes.is_some();
+  let budget = read_options
+    .max_total_bytes
+    .map_or(u64::MAX, |max| max.max(0) as u64);
+
+  // Index of the next candidate to read and the remaining budget
+  let state = Mutex::new((0, budget));
+  let results = Mutex::new(vec![]);
+  std::thread::scope(|scope| {
+    for _ in 0..max_open_files.min(candidates.len()) {
+      scope.spawn(|| {
+        let mut content = vec![];
+        loop {
+          let (idx, reserved) = {
+            let mut state = state.lock().unwrap();
+            let (next, budget) = &mut *state;
+            if *next >= candidates.len() || *budget == 0 {
+              break;
+            }
+
+            let idx = *next;
+            *next += 1;
+
+            if max_size.is_some_and(|max_size| candidates[idx].size > max_size) {
+              continue;
+            }
+
+            if !is_limited {
+              (idx, max_read_bytes)
+            } else {
+              let reserved = candidates[idx].size.min(max_read_bytes).min(*budget);
+              *budget -= reserved;
+              (idx, reserved)
+            }
+          };
+
+          let candidate = &candidates[idx];
+          content.clear();
+          let len = std::fs::File::open(&candidate.full_path)
+            .and_then(|file| file.take(reserved).read_to_end(&mut content));
+
+          let unused = reserved - len.as_ref().map_or(0, |len| *len as u64);
+          if is_limited && unused > 0 {
+            state.lock().unwrap().1 += unused;
+          }
+
+          if len.is_err() {
+            continue;
+          }
+
+          if let Some(value) = f(candidate, &content) {
+            results.lock().unwrap().push((idx, value));
+          }
+        }
+      });
+    }
+  });
+
+  let mut results = results.into_inner().unwrap();
+  results.sort_by_key(|(idx, _)| *idx);
+  results.into_iter().map(|(_, value)| value).collect()
+}
+
+/// Returns matched files containing `needle` literally.
+#[napi]
+pub fn walk_repo_glob_grep(
+  repo_dir: String,
+  glob: String,
+  needle: String,
+  read_options: Option<ReadOptions>,
+  options: Option<WalkOptions>,
+) -> Vec<String> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return vec![];
+  };
+
+  let candidates = walk_repo(&repo_dir, &options, |path, entry| {
+    if matcher.is_match(path) {
+      Some(read_candidate(path, entry))
+    } else {
+      None
+    }
+  });
+
+  let finder = memchr::memmem::Finder::new(needle.as_bytes());
+  let read_options = read_options.unwrap_or_default();
+  let result = read_files(&candidates, &read_options, |candidate, content| {
+    if finder.find(content).is_some() {
+      Some(candidate.path.clone())
+    } else {
+      None
+    }
+  });
+  finish_paths(result, &options)
+}
+
+#[test]
+fn test_walk_repo_glob_grep() {
+  let fixture = make_fixture(&[]);
+  for (file, content) in [
+    ("a.txt", "needle-aaaa"),
+    ("b.txt", "needle-bbbb"),
+    ("c.txt", "haystack"),
+    ("d.txt", "needle"),
+  ] {
+    std::fs::write(fixture.path().join(file), content).unwrap();
+  }
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let glob = "*.txt".to_string();
+  let needle = "needle".to_string();
+
+  let paths = walk_repo_glob_grep(repo.clone(), glob.clone(), needle.clone(), None, None);
+  assert_eq!(
+    paths,
+    vec![
+      "a.txt".to_string(),
+      "b.txt".to_string(),
+      "d.txt".to_string()
+    ]
+  );
+
+  // The budget covers `a.txt` and only the first bytes of `b.txt`
+  let read_options = Some(ReadOptions {
+    max_total_bytes: Some(15),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob_grep(repo, glob, needle, read_options, None);
+  assert_eq!(paths, vec!["a.txt".to_string()]);
+}
+
+#[test]
+fn test_walk_repo_glob_grep_max_read_bytes() {
+  let fixture = make_fixture(&[]);
+  let minified = format!("{}needle", "x".repeat(100));
+  for (file, content) in [("head.js", "/* needle */"), ("bundle.js", &minified)] {
+    std::fs::write(fixture.path().join(file), content).unwrap();
+  }
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let grep = |read_options| {
+    walk_repo_glob_grep(
+      repo.clone(),
+      "*.js".to_string(),
+      "needle".to_string(),
+      read_options,
+      None,
+    )
+  };
+  assert_eq!(grep(None), vec!["bundle.js", "head.js"]);
+
+  let read_options = ReadOptions {
+    max_read_bytes: Some(16),
+    ..Default::default()
+  };
+  assert_eq!(grep(Some(read_options.clone())), vec!["head.js"]);
+
+  // Only the capped reads are counted against the budget
+  let read_options = ReadOptions {
+    max_total_bytes: Some(32),
+    ..read_options
+  };
+  assert_eq!(grep(Some(read_options)), vec!["head.js"]);
+}
+
+#[test]
+fn test_walk_repo_glob_grep_max_open_files() {
+  let fixture = make_fixture(&[]);
+  let mut expected = vec![];
+  for idx in 0..50 {
+    let file = format!("{:02}.txt", idx);
+    let content = if idx % 3 == 0 { "needle" } else { "haystack" };
+    std::fs::write(fixture.path().join(&file), content).unwrap();
+    if idx % 3 == 0 {
+      expected.push(file);
+    }
+  }
+  let repo = fixture.path().to_str().unwrap().to_string();
+
+  for max_open_files in [0, 1, 2, 64] {
+    let read_options = Some(ReadOptions {
+      max_open_files: Some(max_open_files),
+      ..Default::default()
+    });
+    let paths = walk_repo_glob_grep(
+      repo.clone(),
+      "*.txt".to_string(),
+      "needle".to_string(),
+      read_options,
+      None,
+    );
+    assert_eq!(paths, expected);
+  }
+
+  // The budget is consumed in the walk order even with concurrent reads
+  let read_options = Some(ReadOptions {
+    max_total_bytes: Some(14),
+    max_open_files: Some(4),
+    ..Default::default()
+  });
+  let paths = walk_repo_glob_grep(
+    repo,
+    "*.txt".to_string(),
+    "needle".to_string(),
+    read_options,
+    None,
+  );
+  assert_eq!(paths, vec!["00.txt"]);
+}
+
+/// Returns matched files whose first line starts with `prefix`, e.g. `#!/bin/sh`
+/// to find scripts. Only the first bytes of each file are read.
+#[napi]
+pub fn walk_repo_glob_shebang(
+  repo_dir: String,
+  glob: String,
+  prefix: String,
+  options: Option<WalkOptions>,
+) -> Vec<String> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return vec![];
+  };
+
+  let mut head = Vec::with_capacity(prefix.len());
+  walk_repo_paths(&repo_dir, &options, |path, entry| {
+    if !matcher.is_match(path) {
+      return None;
+    }
+
+    head.clear();
+    let file = std::fs::File::open(entry.path()).ok()?;
+    file.take(prefix.len() as u64).read_to_end(&mut head).ok()?;
+    if head == prefix.as_bytes() {
+      Some(path.to_string())
+    } else {
+      None
+    }
+  })
+}
+
+#[test]
+fn test_walk_repo_glob_shebang() {
+  let fixture = make_fixture(&["empty.sh"]);
+  for (file, content) in [
+    ("build.sh", "#!/bin/sh\necho build\n"),
+    ("deploy", "#!/bin/sh -e\n"),
+    ("run.sh", "#!/usr/bin/env bash\n"),
+    ("notes.sh", "# #!/bin/sh\n"),
+  ] {
+    std::fs::write(fixture.path().join(file), content).unwrap();
+  }
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let prefix = "#!/bin/sh".to_string();
+  let paths = walk_repo_glob_shebang(repo.clone(), "*.sh".to_string(), prefix.clone(), None);
+  assert_eq!(paths, vec!["build.sh"]);
+
+  let paths = walk_repo_glob_shebang(repo, "*".to_string(), prefix, None);
+  assert_eq!(paths, vec!["build.sh", "deploy"]);
+}
+
+/// Longest first line returned by `walk_repo_glob_first_lines`, in bytes
+const MAX_FIRST_LINE_BYTES: u64 = 1024;
+
+#[napi(object)]
+pub struct FirstLine {
+  pub path: String,
+  /// Without the line break, missing for binary and unreadable files.
+  /// Lines longer than 1024 bytes are cut.
+  pub first_line: Option<String>,
+}
+
+/// Returns matched files along with their first lines, e.g. shebangs or doctypes
+/// for language detection. Files are read only up to the first line break.
+#[napi]
+pub fn walk_repo_glob_first_lines(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> Vec<FirstLine> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return vec![];
+  };
+
+  let mut line = vec![];
+  walk_repo(&repo_dir, &options, |path, entry| {
+    if !matcher.is_match(path) {
+      return None;
+    }
+
+    line.clear();
+    let first_line = std::fs::File::open(entry.path())
+      .and_then(|file| {
+        let mut reader = std::io::BufReader::new(file.take(MAX_FIRST_LINE_BYTES));
+        reader.read_until(b'\n', &mut line)
+      })
+      .ok()
+      .filter(|_| !line.contains(&0))
+      .and_then(|_| {
+        let line = line.strip_suffix(b"\n").unwrap_or(&line);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        match std::str::from_utf8(line) {
+          Ok(line) => Some(line.to_string()),
+          // A cut line may end in the middle of a character
+          Err(err) if err.error_len().is_none() => {
+            Some(String::from_utf8_lossy(&line[..err.valid_up_to()]).into_owned())
+          }
+          Err(_) => None,
+        }
+      });
+
+    Some(FirstLine {
+      path: output_path(path.to_string(), &options),
+      first_line,
+    })
+  })
+}
+
+#[test]
+fn test_walk_repo_glob_first_lines() {
+  let fixture = make_fixture(&["empty.sh"]);
+  for (file, content) in [
+    ("build.sh", b"#!/bin/sh\necho build\n".as_slice()),
+    ("run.sh", b"#!/usr/bin/env bash\r\nrun\r\n".as_slice()),
+    ("inline.sh", b"echo inline".as_slice()),
+    ("binary.sh", b"\x7fELF\x00\x01\n".as_slice()),
+  ] {
+    std::fs::write(fixture.path().join(file), content).unwrap();
+  }
+  let long_line = format!("#{}", "é".repeat(1000));
+  std::fs::write(fixture.path().join("long.sh"), &long_line).unwrap();
+
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let lines = walk_repo_glob_first_lines(repo, "*.sh".to_string(), None);
+  let lines: Vec<(&str, Option<&str>)> = lines
+    .iter()
+    .map(|line| (line.path.as_str(), line.first_line.as_deref()))
+    .collect();
+  assert_eq!(
+    lines,
+    vec![
+      ("binary.sh", None),
+      ("build.sh", Some("#!/bin/sh")),
+      ("empty.sh", Some("")),
+      ("inline.sh", Some("echo inline")),
+      ("long.sh", Some(&long_line[..1023])),
+      ("run.sh", Some("#!/usr/bin/env bash")),
+    ]
+  );
+}
+
+#[napi(object)]
+pub struct FileContent {
+  pub path: String,
+  /// Missing when the file exceeds `maxSize`, isn't valid UTF-8 or can't be read
+  pub content: Option<String>,
+}
+
+/// Returns matched files along with their contents, e.g. to collect
+/// all `package.json` files at once. Files cut short by `maxTotalBytes`
+/// or `maxReadBytes` have no content either.
+#[napi]
+pub fn walk_repo_glob_read(
+  repo_dir: String,
+  glob: String,
+  read_options: Option<ReadOptions>,
+  options: Option<WalkOptions>,
+) -> Vec<FileContent> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return vec![];
+  };
+
+  let candidates = walk_repo(&repo_dir, &options, |path, entry| {
+    if matcher.is_match(path) {
+      Some(read_candidate(path, entry))
+    } else {
+      None
+    }
+  });
+
+  let read_options = read_options.unwrap_or_default();
+  let mut contents: HashMap<String, String> =
+    read_files(&candidates, &read_options, |candidate, content| {
+      if (content.len() as u64) < candidate.size {
+        return None;
+      }
+
+      let content = std::str::from_utf8(content).ok()?;
+      Some((candidate.path.clone(), content.to_string()))
+    })
+    .into_iter()
+    .collect();
+
+  candidates
+    .into_iter()
+    .map(|candidate| FileContent {
+      content: contents.remove(&candidate.path),
+      path: output_path(candidate.path, &options),
+    })
+    .collect()
+}
+
+#[test]
+fn test_walk_repo_glob_read() {
+  let fixture = make_fixture(&["empty.json"]);
+  for (file, content) in [
+    ("package.json", b"{}".as_slice()),
+    ("big.json", b"[1, 2, 3, 4, 5, 6, 7, 8]".as_slice()),
+    ("binary.json", b"\xff\xfe".as_slice()),
+  ] {
+    std::fs::write(fixture.path().join(file), content).unwrap();
+  }
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let read_options = Some(ReadOptions {
+    max_size: Some(8),
+    ..Default::default()
+  });
+  let files = walk_repo_glob_read(repo, "*.json".to_string(), read_options, None);
+  let contents: Vec<(&str, Option<&str>)> = files
+    .iter()
+    .map(|file| (file.path.as_str(), file.content.as_deref()))
+    .collect();
+  assert_eq!(
+    contents,
+    vec![
+      ("big.json", None),
+      ("binary.json", None),
+      ("empty.json", Some("")),
+      ("package.json", Some("{}")),
+    ]
+  );
+}
+
+#[test]
+fn test_walk_repo_glob_read_repo_files() {
+  let files = walk_repo_glob_read(".".to_string(), "*.toml".to_string(), None, None);
+  assert!(files.iter().any(|file| file.path == "Cargo.toml"));
+  for file in files {
+    let content = file.content.unwrap();
+    assert_eq!(content, std::fs::read_to_string(&file.path).unwrap());
+  }
+}
+
+#[napi]
+pub fn walk_repo_globs(
+  repo_dir: String,
+  globs: Vec<String>,
+  options: Option<WalkOptions>,
+) -> Vec<String> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::new(&repo_dir, &globs, &options) else {
+    return vec![];
+  };
+
+  walk_repo_paths(&repo_dir, &options, |path, _| {
+    if matcher.is_match(path) {
+      Some(path.to_string())
+    } else {
+      None
+    }
+  })
+}
+
+#[test]
+fn test_walk_repo_globs() {
+  let repo = "../renovate".to_string();
+  let globs = vec![
+    "**/package.json".to_string(),
+    "**/package-lock.json".to_string(),
+  ];
+  let paths = walk_repo_globs(repo, globs, None);
+  for path in paths {
+    println!("{}", path);
+  }
+}
+
+/// Same as `walkRepoGlobs`, but throws `RepoNotFound` when `repoDir` doesn't exist
+/// and `InvalidGlob` when none of `globs` can be parsed. Invalid globs given along
+/// with valid ones are still skipped.
+#[napi]
+pub fn walk_repo_globs_checked(
+  repo_dir: String,
+  globs: Vec<String>,
+  options: Option<WalkOptions>,
+) -> napi::Result<Vec<String>, ScanError> {
+  check_repo_dir(&repo_dir)?;
+  let walk_options = options.clone().unwrap_or_default();
+  check_gitmodules(&repo_dir, &walk_options)?;
+  let all_invalid = globs
+    .iter()
+    .all(|glob| compile_glob(&repo_dir, glob, &walk_options).is_none());
+  if !globs.is_empty() && all_invalid {
+    check_globs(&globs)?;
+    let reason = format!("No valid globs among: {}", globs.join(", "));
+    return Err(scan_error(ScanError::InvalidGlob, reason));
+  }
+
+  Ok(walk_repo_globs(repo_dir, globs, options))
+}
+
+#[test]
+fn test_walk_repo_globs_checked() {
+  let fixture = make_fixture(&["package.json", "a.txt"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let globs = vec!["[*.txt".to_string(), "*.json".to_string()];
+  let paths = walk_repo_globs_checked(repo.clone(), globs, None).unwrap();
+  assert_eq!(paths, vec!["package.json"]);
+
+  let globs = vec!["[*.txt".to_string(), "{*.json".to_string()];
+  let err = walk_repo_globs_checked(repo.clone(), globs.clone(), None).unwrap_err();
+  assert_eq!(err.status, ScanError::InvalidGlob);
+  assert!(err.reason.contains("[*.txt"), "{}", err.reason);
+  assert!(walk_repo_globs(repo.clone(), globs, None).is_empty());
+
+  // Valid in general, but not translatable to `globset` syntax
+  let options = Some(WalkOptions {
+    extglob: Some(true),
+    ..Default::default()
+  });
+  let globs = vec!["!(a).txt".to_string()];
+  let err = walk_repo_globs_checked(repo.clone(), globs, options).unwrap_err();
+  assert_eq!(err.status, ScanError::InvalidGlob);
+
+  assert!(walk_repo_globs_checked(repo, vec![], None)
+    .unwrap()
+    .is_empty());
+}
+
+/// Same as `walkRepoGlobs`, but reads globs from `globsFile`, one per line.
+/// Like in `.gitignore`, blank lines and lines starting with `#` are skipped,
+/// and `\#` stands for a literal `#`.
+#[napi]
+pub fn walk_repo_globs_from_file(
+  repo_dir: String,
+  globs_file: String,
+  options: Option<WalkOptions>,
+) -> napi::Result<Vec<String>, ScanError> {
+  let content = std::fs::read_to_string(&globs_file).map_err(|err| {
+    let reason = format!("Failed to read {}: {}", globs_file, err);
+    scan_error(ScanError::IoError, reason)
+  })?;
+  let globs = parse_globs_file(&content);
+  Ok(walk_repo_globs(repo_dir, globs, options))
+}
+
+fn parse_globs_file(content: &str) -> Vec<String> {
+  content
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    .map(|line| {
+      line
+        .strip_prefix('\\')
+        .filter(|rest| rest.starts_with('#'))
+        .unwrap_or(line)
+    })
+    .map(String::from)
+    .collect()
+}
+
+#[test]
+fn test_walk_repo_globs_from_file() {
+  let fixture = make_fixture(&["package.json", "src/index.ts", "#notes.md", "README.md"]);
+  let globs_file = fixture.path().join("globs.txt");
+  std::fs::write(
+    &globs_file,
+    "# Manifests\n**/package.json\n\n  src/*.ts  \r\n\\#*.md\n",
+  )
+  .unwrap();
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let globs_file = globs_file.to_str().unwrap().to_string();
+  let paths = walk_repo_globs_from_file(repo.clone(), globs_file, None).unwrap();
+  assert_eq!(paths, vec!["#notes.md", "package.json", "src/index.ts"]);
+
+  let missing = fixture
+    .path()
+    .join("missing.txt")
+    .to_str()
+    .unwrap()
+    .to_string();
+  let err = walk_repo_globs_from_file(repo, missing, None).unwrap_err();
+  assert_eq!(err.status, ScanError::IoError);
+}
+
+/// Same as `walk_repo_glob`, but only walks the given subdirectories of `repo_dir`,
+/// e.g. specific packages of a monorepo. Paths are still relative to `repo_dir`.
+#[napi]
+pub fn walk_repo_subdirs_glob(
+  repo_dir: String,
+  subdirs: Vec<String>,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> Vec<String> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return vec![];
+  };
+
+  let subdirs = subdirs
+    .iter()
+    .map(|subdir| {
+      let subdir = subdir.replace('\\', "/");
+      let subdir = subdir.trim_start_matches("./").trim_matches('/');
+      subdir.to_string()
+    })
+    .collect();
+
+  let paths = walk_repo_scoped(&repo_dir, Some(subdirs), &options, |path, _| {
+    if matcher.is_match(path) {
+      Some(path.to_string())
+    } else {
+      None
+    }
+  });
+  finish_paths(paths, &options)
+}
+
+#[test]
+fn test_walk_repo_subdirs_glob() {
+  let fixture = make_fixture(&[
+    "package.json",
+    "packages/a/package.json",
+    "packages/a/vendor/lib/package.json",
+    "packages/b/package.json",
+    "packages/c/package.json",
+    "packages/c/node_modules/dep/package.json",
+  ]);
+  std::fs::write(fixture.path().join(".gitignore"), "node_modules\n").unwrap();
+  std::fs::write(
+    fixture.path().join(".gitmodules"),
+    "[submodule \"lib\"]\n  path = packages/a/vendor/lib\n",
+  )
+  .unwrap();
+  gix::init(fixture.path()).unwrap();
+
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let subdirs = vec!["packages/a".to_string(), "./packages/c/".to_string()];
+  let paths = walk_repo_subdirs_glob(repo, subdirs, "**/package.json".to_string(), None);
+  assert_eq!(
+    paths,
+    vec![
+      "packages/a/package.json".to_string(),
+      "packages/c/package.json".to_string(),
+    ]
+  );
+}
+
+/// Same as `walk_repo_globs`, but every glob carries its own matching flags
+#[napi]
+pub fn walk_repo_globs_opts(
+  repo_dir: String,
+  specs: Vec<GlobSpec>,
+  options: Option<WalkOptions>,
+) -> Vec<String> {
+  let options = options.unwrap_or_default();
+  let mut glob_builder = globset::GlobSetBuilder::new();
+  for spec in &specs {
+    let Some(glob) = compile_glob_spec(&repo_dir, spec, &options) else {
+      continue;
+    };
+
+    glob_builder.add(glob);
+  }
+
+  let Some(matcher) = GlobMatcher::build(glob_builder, &options) else {
+    return vec![];
+  };
+
+  walk_repo_paths(&repo_dir, &options, |path, _| {
+    if matcher.is_match(path) {
+      Some(path.to_string())
+    } else {
+      None
+    }
+  })
+}
+
+#[test]
+fn test_walk_repo_globs_opts() {
+  let fixture = make_fixture(&["DATA.JSON", "src/lib.rs", "src/a/mod.rs", "lib/data.json"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let specs = vec![
+    GlobSpec {
+      glob: "*.json".to_string(),
+      case_insensitive: Some(true),
+      literal_separator: Some(true),
+    },
+    GlobSpec {
+      glob: "src/*.rs".to_string(),
+      case_insensitive: None,
+      literal_separator: None,
+    },
+  ];
+  let paths = walk_repo_globs_opts(repo, specs, None);
+  assert_eq!(paths, vec!["DATA.JSON", "src/lib.rs", "src/a/mod.rs"]);
+}
+
+/// Counts files matched by each glob. Duplicate globs share a single counter,
+/// and a file matching several globs is counted once for each of them.
+#[napi]
+pub fn walk_repo_globs_counts(
+  repo_dir: String,
+  globs: Vec<String>,
+  options: Option<WalkOptions>,
+) -> HashMap<String, u32> {
+  let options = options.unwrap_or_default();
+  let mut keys: Vec<&String> = vec![];
+  let mut glob_builder = globset::GlobSetBuilder::new();
+  for glob in &globs {
+    if keys.contains(&glob) {
+      continue;
+    }
+
+    let Some(compiled) = compile_glob(&repo_dir, glob, &options) else {
+      continue;
+    };
+
+    keys.push(glob);
+    glob_builder.add(compiled);
+  }
+
+  let Some(matcher) = GlobMatcher::build(glob_builder, &options) else {
+    return HashMap::new();
+  };
+
+  let matches = walk_repo(&repo_dir, &options, |path, _| {
+    let indices = matcher.matches(path);
+    if indices.is_empty() {
+      None
+    } else {
+      Some(indices)
+    }
+  });
+
+  let mut result: HashMap<String, u32> = keys.iter().map(|key| (key.to_string(), 0)).collect();
+  for idx in matches.into_iter().flatten() {
+    if let Some(count) = result.get_mut(keys[idx]) {
+      *count += 1;
+    }
+  }
+  result
+}
+
+#[test]
+fn test_walk_repo_globs_counts() {
+  let fixture = make_fixture(&["package.json", "a/package.json", "a/tsconfig.json"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let globs = vec![
+    "**/package.json".to_string(),
+    "**/*.json".to_string(),
+    "**/*.json".to_string(),
+    "*.yml".to_string(),
+  ];
+  let counts = walk_repo_globs_counts(repo, globs, None);
+  assert_eq!(counts.len(), 3);
+  assert_eq!(counts["**/package.json"], 2);
+  assert_eq!(counts["**/*.json"], 3);
+  assert_eq!(counts["*.yml"], 0);
+}
+
+#[napi(object)]
+pub struct UnmatchedReport {
+  pub paths: Vec<String>,
+  /// Valid globs which matched no files, in the order given
+  pub unmatched_globs: Vec<String>,
+}
+
+/// Same as `walkRepoGlobs`, but also reports globs which never matched,
+/// helping to catch patterns that silently never fire. Invalid globs
+/// aren't reported, as they're skipped anyway.
+#[napi]
+pub fn walk_repo_globs_unmatched(
+  repo_dir: String,
+  globs: Vec<String>,
+  options: Option<WalkOptions>,
+) -> UnmatchedReport {
+  let options = options.unwrap_or_default();
+  let mut keys: Vec<&String> = vec![];
+  let mut glob_builder = globset::GlobSetBuilder::new();
+  for glob in &globs {
+    if keys.contains(&glob) {
+      continue;
+    }
+
+    let Some(compiled) = compile_glob(&repo_dir, glob, &options) else {
+      continue;
+    };
+
+    keys.push(glob);
+    glob_builder.add(compiled);
+  }
+
+  let Some(matcher) = GlobMatcher::build(glob_builder, &options) else {
+    return UnmatchedReport {
+      paths: vec![],
+      unmatched_globs: vec![],
+    };
+  };
+
+  let mut matched = vec![false; keys.len()];
+  let paths = walk_repo(&repo_dir, &options, |path, _| {
+    let indices = matcher.matches(path);
+    for &idx in &indices {
+      matched[idx] = true;
+    }
+
+    if indices.is_empty() {
+      None
+    } else {
+      Some(path.to_string())
+    }
+  });
+
+  let unmatched_globs = keys
+    .into_iter()
+    .zip(matched)
+    .filter(|(_, matched)| !matched)
+    .map(|(glob, _)| glob.to_string())
+    .collect();
+
+  UnmatchedReport {
+    paths: finish_paths(paths, &options),
+    unmatched_globs,
+  }
+}
+
+#[test]
+fn test_walk_repo_globs_unmatched() {
+  let fixture = make_fixture(&["package.json", "a/package.json"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let globs = vec![
+    "**/package.json".to_string(),
+    "deeply/nested/nonsense/*.cfg".to_string(),
+    "[invalid".to_string(),
+    "*.yml".to_string(),
+    "*.yml".to_string(),
+  ];
+  let report = walk_repo_globs_unmatched(repo, globs, None);
+  assert_eq!(report.paths, vec!["package.json", "a/package.json"]);
+  assert_eq!(
+    report.unmatched_globs,
+    vec!["deeply/nested/nonsense/*.cfg", "*.yml"]
+  );
+}
+
+const MAX_SUGGESTIONS: usize = 10;
+
+#[napi(object)]
+pub struct SuggestedPaths {
+  pub paths: Vec<String>,
+  /// Up to 10 files whose names match the last segment of the glob,
+  /// filled only when nothing matched the glob itself
+  pub suggestions: Vec<String>,
+}
+
+/// Same as `walkRepoGlob`, but when nothing matches, suggests files that would
+/// match if only names were compared, e.g. `lib/index.ts` for `src/index.ts`.
+#[napi]
+pub fn walk_repo_glob_with_suggestions(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> SuggestedPaths {
+  let options = options.unwrap_or_default();
+  let basename_options = WalkOptions {
+    match_basename: Some(true),
+    ..options.clone()
+  };
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
+    return SuggestedPaths {
+      paths: vec![],
+      suggestions: vec![],
+    };
+  };
+
+  let basename_glob = glob.rsplit('/').next().unwrap_or(&glob);
+  let relaxed_matcher = GlobMatcher::single(&repo_dir, basename_glob, &basename_options);
+
+  let mut suggestions = vec![];
+  let paths = walk_repo(&repo_dir, &options, |path, _| {
+    if matcher.is_match(path) {
+      return Some(path.to_string());
+    }
+
+    let is_relaxed_match = relaxed_matcher
+      .as_ref()
+      .is_some_and(|matcher| matcher.is_match(path));
+    if suggestions.len() < MAX_SUGGESTIONS && is_relaxed_match {
+      suggestions.push(output_path(path.to_string(), &options));
+    }
+    None
+  });
+
+  if !paths.is_empty() {
+    suggestions.clear();
+  }
+
+  SuggestedPaths {
+    paths: finish_paths(paths, &options),
+    suggestions,
+  }
+}
+
+#[test]
+fn test_walk_repo_glob_with_suggestions() {
+  let fixture = make_fixture(&["lib/index.ts", "lib/util/index.ts", "lib/main.ts"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let result = walk_repo_glob_with_suggestions(repo.clone(), "src/index.ts".to_string(), None);
+  assert!(result.paths.is_empty());
+  assert_eq!(
+    result.suggestions,
+    vec!["lib/index.ts", "lib/util/index.ts"]
+  );
+
+  let result = walk_repo_glob_with_suggestions(repo, "lib/*.ts".to_string(), None);
+  assert_eq!(
+    result.paths,
+    vec!["lib/index.ts", "lib/main.ts", "lib/util/index.ts"]
+  );
+  assert!(result.suggestions.is_empty());
+}
+
+#[napi(object)]
+pub struct GlobMask {
+  pub path: String,
+  /// Bit `i` is set when `globs[i]` matched the path
+  pub mask: f64,
+}
+
+/// Largest number of globs whose bits fit into the `f64` mantissa
+const MAX_MASK_GLOBS: usize = 53;
+
+#[napi]
+pub fn walk_repo_globs_bitset(
+  repo_dir: String,
+  globs: Vec<String>,
+  options: Option<WalkOptions>,
+) -> napi::Result<Vec<GlobMask>> {
+  if globs.len() > MAX_MASK_GLOBS {
+    return Err(napi::Error::new(
+      napi::Status::InvalidArg,
+      format!(
+        "At most {} globs are supported, got {}",
+        MAX_MASK_GLOBS,
+        globs.len()
+      ),
+    ));
+  }
+
+  let options = options.unwrap_or_default();
+  let mut bits: Vec<u64> = vec![];
+  let mut glob_builder = globset::GlobSetBuilder::new();
+  for (idx, glob) in globs.iter().enumerate() {
+    let Some(glob) = compile_glob(&repo_dir, glob, &options) else {
+      continue;
+    };
+
+    bits.push(1 << idx);
+    glob_builder.add(glob);
+  }
+
+  let Some(matcher) = GlobMatcher::build(glob_builder, &options) else {
+    return Ok(vec![]);
+  };
+
+  let result = walk_repo(&repo_dir, &options, |path, _| {
+    let mask = matcher
+      .matches(path)
+      .into_iter()
+      .fold(0, |mask, idx| mask | bits[idx]);
+
+    if mask == 0 {
+      None
+    } else {
+      Some(GlobMask {
+        path: output_path(path.to_string(), &options),
+        mask: mask as f64,
+      })
+    }
+  });
+  Ok(result)
+}
+
+#[test]
+fn test_walk_repo_globs_bitset() {
+  let fixture = make_fixture(&["src/main.rs", "README.md"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let globs = vec![
+    "src/**".to_string(),
+    "*.md".to_string(),
+    "**/*.rs".to_string(),
+  ];
+  let masks = walk_repo_globs_bitset(repo.clone(), globs, None).unwrap();
+  assert_eq!(masks.len(), 2);
+  assert_eq!(masks[0].path, "README.md");
+  assert_eq!(masks[0].mask, 2.0);
+  assert_eq!(masks[1].path, "src/main.rs");
+  assert_eq!(masks[1].mask, 5.0);
+
+  let globs = vec!["*.md".to_string(); MAX_MASK_GLOBS + 1];
+  assert!(walk_repo_globs_bitset(repo, globs, None).is_err());
+}
+
+#[napi(object)]
+pub struct GlobPartition {
+  pub matched: Vec<String>,
+  pub unmatched: Vec<String>,
+}
+
+/// Splits all walked files into those matching any of `globs` and the rest
+/// in a single traversal, e.g. for finding files not covered by any rule.
+#[napi]
+pub fn walk_repo_globs_partition(
+  repo_dir: String,
+  globs: Vec<String>,
+  options: Option<WalkOptions>,
+) -> GlobPartition {
+  let options = options.unwrap_or_default();
+  let matcher = GlobMatcher::new(&repo_dir, &globs, &options);
+
+  let mut matched = vec![];
+  let mut unmatched = vec![];
+  walk_repo(&repo_dir, &options, |path, _| {
+    if matcher
+      .as_ref()
+      .is_some_and(|matcher| matcher.is_match(path))
+    {
+      matched.push(path.to_string());
+    } else {
+      unmatched.push(path.to_string());
+    }
+    None::<()>
+  });
+
+  GlobPartition {
+    matched: finish_paths(matched, &options),
+    unmatched: finish_paths(unmatched, &options),
+  }
+}
+
+#[test]
+fn test_walk_repo_globs_partition() {
+  let fixture = make_fixture(&[
+    "README.md",
+    "src/main.rs",
+    "src/lib.rs",
+    "docs/guide.md",
+    "Cargo.toml",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let globs = vec!["**/*.rs".to_string(), "Cargo.toml".to_string()];
+  let partition = walk_repo_globs_partition(repo.clone(), globs, None);
+  assert_eq!(
+    partition.matched,
+    vec!["Cargo.toml", "src/lib.rs", "src/main.rs"]
+  );
+  assert_eq!(partition.unmatched, vec!["README.md", "docs/guide.md"]);
+
+  let mut all = [partition.matched, partition.unmatched].concat();
+  all.sort();
+  let mut expected = walk_repo_glob(repo, "**".to_string(), None);
+  expected.sort();
+  assert_eq!(all, expected);
+}
+
+#[napi]
+pub fn walk_repo_include_exclude(
+  repo_dir: String,
+  include: Vec<String>,
+  exclude: Vec<String>,
+  options: Option<WalkOptions>,
+) -> Vec<String> {
+  let options = options.unwrap_or_default();
+  let Some(include) = GlobMatcher::new(&repo_dir, &include, &options) else {
+    return vec![];
+  };
+
+  let Some(exclude) = GlobMatcher::new(&repo_dir, &exclude, &options) else {
+    return vec![];
+  };
+
+  walk_repo_paths(&repo_dir, &options, |path, _| {
+    if include.is_match(path) && !exclude.is_match(path) {
+      Some(path.to_string())
+    } else {
+      None
+    }
+  })
+}
+
+#[test]
+fn test_walk_repo_include_exclude() {
+  let fixture = make_fixture(&[
+    "src/app.js",
+    "src/min/app.js",
+    "lib/min/util.js",
+    "lib/util.js",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let include = vec!["**/*.js".to_string()];
+  let exclude = vec!["**/min/**".to_string()];
+  let paths = walk_repo_include_exclude(repo, include, exclude, None);
+  assert_eq!(
+    paths,
+    vec!["lib/util.js".to_string(), "src/app.js".to_string()]
+  );
+}
+
+/// Walks files not excluded by `patterns` processed in order, where
+/// `!`-prefixed patterns re-include files excluded by earlier ones.
+/// Like in `.gitignore`, the last matching pattern wins, patterns without
+/// a slash match at any depth, and a leading `/` anchors a pattern to the root.
+#[napi]
+pub fn walk_repo_gitignore_style(
+  repo_dir: String,
+  patterns: Vec<String>,
+  options: Option<WalkOptions>,
+) -> Vec<String> {
+  let options = options.unwrap_or_default();
+  let mut negations = vec![];
+  let mut glob_builder = globset::GlobSetBuilder::new();
+  for pattern in &patterns {
+    let (negated, pattern) = match pattern.strip_prefix('!') {
+      Some(pattern) => (true, pattern),
+      None => (false, pattern.as_str()),
+    };
+
+    let pattern = if pattern.contains('/') {
+      Cow::Borrowed(pattern)
+    } else {
+      Cow::Owned(format!("**/{}", pattern))
+    };
+
+    let Some(glob) = compile_glob(&repo_dir, &pattern, &options) else {
+      continue;
+    };
+
+    glob_builder.add(glob);
+    negations.push(negated);
+  }
+
+  let Some(matcher) = GlobMatcher::build(glob_builder, &options) else {
+    return vec![];
+  };
+
+  walk_repo_paths(&repo_dir, &options, |path, _| {
+    let is_excluded = match matcher.matches(path).last() {
+      Some(idx) => !negations[*idx],
+      None => false,
+    };
+
+    if is_excluded {
+      None
+    } else {
+      Some(path.to_string())
+    }
+  })
+}
+
+#[test]
+fn test_walk_repo_gitignore_style() {
+  let fixture = make_fixture(&[
+    "app.log",
+    "keep.log",
+    "logs/debug.log",
+    "src/main.rs",
+    "sub/keep.log",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let patterns = vec!["**/*.log".to_string(), "!keep.log".to_string()];
+  let paths = walk_repo_gitignore_style(repo.clone(), patterns, None);
+  assert_eq!(
+    paths,
+    vec![
+      "keep.log".to_string(),
+      "src/main.rs".to_string(),
+      "sub/keep.log".to_string()
+    ]
+  );
+
+  // A leading slash anchors the pattern to the root
+  let patterns = vec!["**/*.log".to_string(), "!/keep.log".to_string()];
+  let paths = walk_repo_gitignore_style(repo.clone(), patterns, None);
+  assert_eq!(
+    paths,
+    vec!["keep.log".to_string(), "src/main.rs".to_string()]
+  );
+
+  // Later exclusions take precedence over earlier negations
+  let patterns = vec![
+    "**/*.log".to_string(),
+    "!keep.log".to_string(),
+    "*.log".to_string(),
+  ];
+  let paths = walk_repo_gitignore_style(repo, patterns, None);
+  assert_eq!(paths, vec!["src/main.rs".to_string()]);
+}
+
+/// Same as `walkRepoGitignoreStyle`, but reads patterns from `patternFiles` in order,
+/// so that later files override earlier ones, e.g. a base ruleset and a local one.
+/// Files use the format of `walkRepoGlobsFromFile`. Missing files throw `IoError`,
+/// unless `skipMissing` is set.
+#[napi]
+pub fn walk_repo_glob_files(
+  repo_dir: String,
+  pattern_files: Vec<String>,
+  skip_missing: Option<bool>,
+  options: Option<WalkOptions>,
+) -> napi::Result<Vec<String>, ScanError> {
+  let mut patterns = vec![];
+  for pattern_file in &pattern_files {
+    let content = match std::fs::read_to_string(pattern_file) {
+      Ok(content) => content,
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound && skip_missing == Some(true) => {
+        continue;
+      }
+      Err(err) => {
+        let reason = format!("Failed to read {}: {}", pattern_file, err);
+        return Err(scan_error(ScanError::IoError, reason));
+      }
+    };
+
+    patterns.extend(parse_globs_file(&content));
+  }
+
+  Ok(walk_repo_gitignore_style(repo_dir, patterns, options))
+}
+
+#[test]
+fn test_walk_repo_glob_files() {
+  let fixture = make_fixture(&["app.log", "keep.log", "logs/debug.log", "src/main.rs"]);
+  let rules = tempfile::tempdir().unwrap();
+  let base = rules.path().join("base");
+  let overrides = rules.path().join("overrides");
+  std::fs::write(&base, "# Logs\n**/*.log\n").unwrap();
+  std::fs::write(&overrides, "!keep.log\n").unwrap();
+  let missing = rules.path().join("missing");
+
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let base = base.to_str().unwrap().to_string();
+  let overrides = overrides.to_str().unwrap().to_string();
+  let missing = missing.to_str().unwrap().to_string();
+
+  let files = vec![base.clone()];
+  let paths = walk_repo_glob_files(repo.clone(), files, None, None).unwrap();
+  assert_eq!(paths, vec!["src/main.rs"]);
+
+  let files = vec![base.clone(), overrides.clone()];
+  let paths = walk_repo_glob_files(repo.clone(), files, None, None).unwrap();
+  assert_eq!(paths, vec!["keep.log", "src/main.rs"]);
+
+  let files = vec![base, missing.clone(), overrides];
+  let err = walk_repo_glob_files(repo.clone(), files.clone(), None, None).unwrap_err();
+  assert_eq!(err.status, ScanError::IoError);
+  assert!(err.reason.contains(&missing), "{}", err.reason);
+
+  let paths = walk_repo_glob_files(repo, files, Some(true), None).unwrap();
+  assert_eq!(paths, vec!["keep.log", "src/main.rs"]);
+}
+
+struct Pathspec<'a> {
+  pattern: &'a str,
+  glob: bool,
+  icase: bool,
+  exclude: bool,
+}
+
+/// Parses supported pathspec magic: `glob`, `icase` and `exclude`,
+/// including the `:!` and `:^` short forms
+fn parse_pathspec(spec: &str) -> Option<Pathspec<'_>> {
+  let mut pathspec = Pathspec {
+    pattern: spec,
+    glob: false,
+    icase: false,
+    exclude: false,
+  };
+
+  if let Some(rest) = spec.strip_prefix(":(") {
+    let (magic, pattern) = rest.split_once(')')?;
+    for word in magic.split(',') {
+      match word.trim() {
+        "glob" => pathspec.glob = true,
+        "icase" => pathspec.icase = true,
+        "exclude" => pathspec.exclude = true,
+        _ => return None,
+      }
+    }
+    pathspec.pattern = pattern;
+  } else if let Some(pattern) = spec.strip_prefix(":!").or_else(|| spec.strip_prefix(":^")) {
+    pathspec.exclude = true;
+    pathspec.pattern = pattern;
+  }
+
+  Some(pathspec)
+}
+
+#[test]
+fn test_parse_pathspec() {
+  let spec = parse_pathspec(":(glob,icase)src/*.rs").unwrap();
+  assert_eq!(spec.pattern, "src/*.rs");
+  assert!(spec.glob && spec.icase && !spec.exclude);
+
+  let spec = parse_pathspec(":!**/test/**").unwrap();
+  assert_eq!(spec.pattern, "**/test/**");
+  assert!(!spec.glob && !spec.icase && spec.exclude);
+
+  assert!(parse_pathspec(":(attr:foo)bar").is_none());
+}
+
+/// Walks files matching a subset of git pathspec syntax.
+///
+/// Like in git, `*` matches `/` unless `:(glob)` magic is used, and a pathspec
+/// matching a directory matches everything inside of it. When only exclusions
+/// are given, every other file is included.
+#[napi]
+pub fn walk_repo_pathspec(
+  repo_dir: String,
+  pathspecs: Vec<String>,
+  options: Option<WalkOptions>,
+) -> Vec<String> {
+  let options = options.unwrap_or_default();
+
+  let mut include_builder = globset::GlobSetBuilder::new();
+  let mut exclude_builder = globset::GlobSetBuilder::new();
+  let mut has_includes = false;
+  for spec in &pathspecs {
+    let Some(spec) = parse_pathspec(spec) else {
+      continue;
+    };
+
+    let pattern = spec.pattern.trim_end_matches('/');
+    for pattern in [pattern.to_string(), format!("{}/**", pattern)] {
+      let Ok(glob) = globset::GlobBuilder::new(&pattern)
+        .literal_separator(spec.glob)
+        .case_insensitive(spec.icase || options.case_insensitive == Some(true))
+        .build()
+      else {
+        continue;
+      };
+
+      if spec.exclude {
+        exclude_builder.add(glob);
+      } else {
+        include_builder.add(glob);
+        has_includes = true;
+      }
+    }
+  }
+
+  let Some(include) = GlobMatcher::build(include_builder, &options) else {
+    return vec![];
+  };
+
+  let Some(exclude) = GlobMatcher::build(exclude_builder, &options) else {
+    return vec![];
+  };
+
+  walk_repo_paths(&repo_dir, &options, |path, _| {
+    if (!has_includes || include.is_match(path)) && !exclude.is_match(path) {
+      Some(path.to_string())
+    } else {
+      None
+    }
+  })
+}
+
+#[test]
+fn test_walk_repo_pathspec() {
+  let fixture = make_fixture(&[
+    "src/lib.rs",
+    "src/test/lib.rs",
+    "src/a/b.rs",
+    "test/main.rs",
+    "README.md",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+
+  let pathspecs = vec!["*.rs".to_string(), ":!**/test/**".to_string()];
+  let paths = walk_repo_pathspec(repo.clone(), pathspecs, None);
+  assert_eq!(paths, vec!["src/lib.rs", "src/a/b.rs"]);
+
+  let pathspecs = vec![":(glob)src/*.rs".to_string()];
+  let paths = walk_repo_pathspec(repo.clone(), pathspecs, None);
+  assert_eq!(paths, vec!["src/lib.rs"]);
+
+  let pathspecs = vec![":(icase)SRC/A".to_string()];
+  let paths = walk_repo_pathspec(repo.clone(), pathspecs, None);
+  assert_eq!(paths, vec!["src/a/b.rs"]);
+
+  let pathspecs = vec![":(exclude)src".to_string(), ":^test".to_string()];
+  let paths = walk_repo_pathspec(repo, pathspecs, None);
+  assert_eq!(paths, vec!["README.md"]);
+}
+
+#[napi]
+pub fn walk_repo_globs_map(
+  repo_dir: String,
+  globs_map: HashMap<String, Vec<String>>,
+  options: Option<WalkOptions>,
+) -> HashMap<String, Vec<String>> {
+  collect_globs_map(&repo_dir, &globs_map, &options.unwrap_or_default(), None)
+    .into_iter()
+    .map(|(key, (paths, _))| (key, paths))
+    .collect()
+}
+
+/// Collects paths per key, keeping at most `max_per_key` of them.
+/// Values are paired with whether any paths were dropped for the key.
+fn collect_globs_map(
+  repo_dir: &str,
+  globs_map: &HashMap<String, Vec<String>>,
+  options: &WalkOptions,
+  max_per_key: Option<usize>,
+) -> HashMap<String, (Vec<String>, bool)> {
+  let mut accum: HashMap<&String, (Vec<String>, bool)> = HashMap::new();
+  let matchers: Vec<(&String, GlobMatcher)> = globs_map
+    .iter()
+    .filter_map(|(key, globs)| {
+      accum.insert(key, (Vec::new(), false));
+      let matcher = GlobMatcher::new(repo_dir, globs, options)?;
+      Some((key, matcher))
+    })
+    .collect();
+
+  let match_basename = options.match_basename == Some(true);
+  let mount_prefix = mount_prefix(options);
+  let unicode_normalize = options.unicode_normalize == Some(true);
+  walk_repo(repo_dir, options, |path: &str, _| {
+    // Parsed once per path rather than once per key
+    let subject = match_subject(path, match_basename, mount_prefix, unicode_normalize);
+    let candidate = globset::Candidate::new(subject.as_ref());
+    for (key, matcher) in &matchers {
+      if !matcher.is_match_candidate(&candidate) {
+        continue;
+      }
+
+      if let Some((paths, truncated)) = accum.get_mut(key) {
+        if max_per_key.is_some_and(|max| paths.len() >= max) {
+          *truncated = true;
+        } else {
+          paths.push(path.to_string());
+        }
+      }
+    }
+    None::<()>
+  });
+
+  let mut result = HashMap::new();
+  for (key, (paths, truncated)) in accum {
+    result.insert(key.to_string(), (finish_paths(paths, options), truncated));
+  }
+  result
+}
+
+#[test]
+fn test_walk_repo_globs_map() {
+  let repo = "../renovate".to_string();
+  let mut globs_map = HashMap::new();
+  globs_map.insert(
+    "package".to_string(),
+    vec![
+      "**/package.json".to_string(),
+      "**/package-lock.json".to_string(),
+    ],
+  );
+  globs_map.insert(
+    "lock".to_string(),
+    vec![
+      "**/yarn.lock".to_string(),
+      "**/package-lock.json".to_string(),
+    ],
+  );
+  let paths_map = walk_repo_globs_map(repo, globs_map, None);
+  for (key, paths) in paths_map {
+    for path in paths {
+      println!("{}: {}", key, path);
+    }
+  }
+}
+
+#[test]
+fn test_walk_repo_globs_map_same_as_per_key_walks() {
+  let fixture = make_fixture(&[
+    "package.json",
+    "yarn.lock",
+    "src/index.ts",
+    "src/util/index.ts",
+    "lib/a/package.json",
+    "lib/a/package-lock.json",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let mut globs_map = HashMap::new();
+  globs_map.insert("package".to_string(), vec!["**/package.json".to_string()]);
+  globs_map.insert(
+    "lock".to_string(),
+    vec!["**/yarn.lock".to_string(), "**/*-lock.json".to_string()],
+  );
+  globs_map.insert("index".to_string(), vec!["index.ts".to_string()]);
+  globs_map.insert("src".to_string(), vec!["src/*".to_string()]);
+
+  for match_basename in [None, Some(true)] {
+    let options = WalkOptions {
+      match_basename,
+      ..Default::default()
+    };
+    let paths_map = walk_repo_globs_map(repo.clone(), globs_map.clone(), Some(options.clone()));
+    for (key, globs) in &globs_map {
+      let expected = walk_repo_globs(repo.clone(), globs.clone(), Some(options.clone()));
+      assert_eq!(paths_map[key], expected, "key {key}");
+    }
+  }
+}
+
+#[napi(object)]
+pub struct CountedPaths {
+  pub count: u32,
+  pub paths: Vec<String>,
+}
+
+/// Same as `walkRepoGlobsMap`, but also returns the number of paths per key.
+#[napi]
+pub fn walk_repo_globs_map_counted(
+  repo_dir: String,
+  globs_map: HashMap<String, Vec<String>>,
+  options: Option<WalkOptions>,
+) -> HashMap<String, CountedPaths> {
+  walk_repo_globs_map(repo_dir, globs_map, options)
+    .into_iter()
+    .map(|(key, paths)| {
+      let count = paths.len() as u32;
+      (key, CountedPaths { count, paths })
     })
     .collect()
 }
 
 #[test]
-fn test_read_submodule_paths() {
-  let gitmodules = r#"
-    [submodule "foo/bar/baz"]
-            path = foo/bar/baz
-            url = https://github.com/zharinov/good-enough-parser
-  "#;
-  let paths = read_submodule_paths(gitmodules).unwrap();
-  assert_eq!(paths, vec!["foo/bar/baz".to_string()]);
+fn test_walk_repo_globs_map_counted() {
+  let fixture = make_fixture(&[
+    "package.json",
+    "yarn.lock",
+    "a/package.json",
+    "b/package.json",
+    "b/package-lock.json",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let mut globs_map = HashMap::new();
+  globs_map.insert("package".to_string(), vec!["**/package.json".to_string()]);
+  globs_map.insert(
+    "lock".to_string(),
+    vec![
+      "**/yarn.lock".to_string(),
+      "**/package-lock.json".to_string(),
+    ],
+  );
+  globs_map.insert("none".to_string(), vec!["**/*.rs".to_string()]);
+
+  let counted = walk_repo_globs_map_counted(repo, globs_map, None);
+  for value in counted.values() {
+    assert_eq!(value.count as usize, value.paths.len());
+  }
+  assert_eq!(counted["package"].count, 3);
+  assert_eq!(counted["lock"].count, 2);
+  assert_eq!(counted["none"].count, 0);
 }
 
-fn get_submodule_paths(repo_path: &Path) -> Option<Vec<String>> {
-  repo_path
-    .join(".gitmodules")
-    .to_str()
-    .and_then(|gitmodules| std::fs::read_to_string(gitmodules).ok())
-    .and_then(|gitmodules| read_submodule_paths(&gitmodules))
+#[napi(object)]
+pub struct CappedPaths {
+  pub paths: Vec<String>,
+  /// Whether more paths matched than `maxPerKey`
+  pub truncated: bool,
 }
 
-fn walk_repo<F, Res>(repo_dir: &str, f: F) -> Vec<Res>
-where
-  F: Fn(&Path) -> Option<Res>,
-{
-  let repo_path = Path::new(repo_dir);
+/// Same as `walkRepoGlobsMap`, but collects at most `maxPerKey` paths per key,
+/// keeping memory use bounded when a glob matches far more files than expected.
+/// The walk continues for keys which haven't reached the limit.
+#[napi]
+pub fn walk_repo_globs_map_capped(
+  repo_dir: String,
+  globs_map: HashMap<String, Vec<String>>,
+  max_per_key: u32,
+  options: Option<WalkOptions>,
+) -> HashMap<String, CappedPaths> {
+  let options = options.unwrap_or_default();
+  let max_per_key = Some(max_per_key as usize);
+  collect_globs_map(&repo_dir, &globs_map, &options, max_per_key)
+    .into_iter()
+    .map(|(key, (paths, truncated))| (key, CappedPaths { paths, truncated }))
+    .collect()
+}
+
+#[test]
+fn test_walk_repo_globs_map_capped() {
+  let fixture = make_fixture(&["a.json", "b.json", "c.json", "d/e.json", "package.json"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let mut globs_map = HashMap::new();
+  globs_map.insert("all".to_string(), vec!["**/*.json".to_string()]);
+  globs_map.insert("package".to_string(), vec!["package.json".to_string()]);
+
+  let capped = walk_repo_globs_map_capped(repo, globs_map, 2, None);
+  assert_eq!(capped["all"].paths, vec!["a.json", "b.json"]);
+  assert!(capped["all"].truncated);
+  assert_eq!(capped["package"].paths, vec!["package.json"]);
+  assert!(!capped["package"].truncated);
+}
+
+#[napi(object)]
+pub struct IndexedMatch {
+  pub key: String,
+  /// Position of the matched glob in the key's list
+  pub glob_index: u32,
+  pub path: String,
+}
 
-  let submodule_paths = get_submodule_paths(repo_path);
+/// Reports which glob of which key matched each path, to help debugging
+/// `walkRepoGlobsMap` configs. A path is reported once for every matching glob,
+/// with keys in sorted order. Invalid globs are skipped without shifting indices.
+#[napi]
+pub fn walk_repo_globs_map_indexed(
+  repo_dir: String,
+  globs_map: HashMap<String, Vec<String>>,
+  options: Option<WalkOptions>,
+) -> Vec<IndexedMatch> {
+  let options = options.unwrap_or_default();
+  let mut keys: Vec<&String> = globs_map.keys().collect();
+  keys.sort();
 
-  let submodule_glob = if let Some(paths) = submodule_paths {
+  let mut matchers: Vec<(&String, Vec<usize>, GlobMatcher)> = vec![];
+  for key in keys {
+    let mut indices = vec![];
     let mut glob_builder = globset::GlobSetBuilder::new();
-    for path in paths {
-      if let Ok(glob) = Glob::new(&path) {
-        glob_builder.add(glob);
-      }
-    }
-    glob_builder.build().ok()
-  } else {
-    None
-  };
+    for (idx, glob) in globs_map[key].iter().enumerate() {
+      let Some(compiled) = compile_glob(&repo_dir, glob, &options) else {
+        continue;
+      };
 
-  let mut walk_builder = ignore::WalkBuilder::new(repo_path);
-  walk_builder.follow_links(false);
-  walk_builder.parents(false);
-  walk_builder.hidden(false);
-  walk_builder.git_exclude(false);
+      indices.push(idx);
+      glob_builder.add(compiled);
+    }
 
-  let prefix = repo_dir.to_string();
-  walk_builder.filter_entry(move |entry| {
-    let Some(file_type) = entry.file_type() else {
-      return false;
-    };
+    if let Some(matcher) = GlobMatcher::build(glob_builder, &options) {
+      matchers.push((key, indices, matcher));
+    }
+  }
 
-    if file_type.is_file() {
-      return true;
+  let matches = walk_repo(&repo_dir, &options, |path, _| {
+    let mut result = vec![];
+    for (key, indices, matcher) in &matchers {
+      for idx in matcher.matches(path) {
+        result.push(IndexedMatch {
+          key: key.to_string(),
+          glob_index: indices[idx] as u32,
+          path: output_path(path.to_string(), &options),
+        });
+      }
     }
 
-    if file_type.is_symlink() {
-      return false;
+    if result.is_empty() {
+      None
+    } else {
+      Some(result)
     }
+  });
+  matches.into_iter().flatten().collect()
+}
 
-    let Ok(path) = entry.path().strip_prefix(&prefix) else {
-      return false;
-    };
+#[test]
+fn test_walk_repo_globs_map_indexed() {
+  let fixture = make_fixture(&["package.json", "yarn.lock", "a/package-lock.json"]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let mut globs_map = HashMap::new();
+  globs_map.insert(
+    "lock".to_string(),
+    vec![
+      "**/yarn.lock".to_string(),
+      "[invalid".to_string(),
+      "**/package-lock.json".to_string(),
+    ],
+  );
+  globs_map.insert("package".to_string(), vec!["**/package.json".to_string()]);
 
-    let Some(dir_name) = path.file_name() else {
-      return false;
-    };
+  let matches = walk_repo_globs_map_indexed(repo, globs_map, None);
+  let tags: Vec<(&str, u32, &str)> = matches
+    .iter()
+    .map(|m| (m.key.as_str(), m.glob_index, m.path.as_str()))
+    .collect();
+  assert_eq!(
+    tags,
+    vec![
+      ("package", 0, "package.json"),
+      ("lock", 0, "yarn.lock"),
+      ("lock", 2, "a/package-lock.json"),
+    ]
+  );
+}
 
-    let Some(dir_name) = dir_name.to_str() else {
-      return false;
-    };
+#[napi(object)]
+pub struct GlobDiagnostic {
+  pub glob: String,
+  pub error: String,
+}
 
-    if dir_name == ".git" {
-      return false;
-    }
+/// Lists the globs of each key which `walkRepoGlobsMap` would skip as invalid
+/// with these options, without walking anything. Keys with valid globs only are left out.
+#[napi]
+pub fn validate_globs_map(
+  globs_map: HashMap<String, Vec<String>>,
+  options: Option<WalkOptions>,
+) -> HashMap<String, Vec<GlobDiagnostic>> {
+  // Only changes how directories match, and would look them up on disk
+  let options = WalkOptions {
+    dir_as_recursive: None,
+    ..options.unwrap_or_default()
+  };
 
-    if let Some(glob) = &submodule_glob {
-      if glob.is_match(path) {
-        return false;
-      }
-    }
+  globs_map
+    .into_iter()
+    .filter_map(|(key, globs)| {
+      let diagnostics: Vec<GlobDiagnostic> = globs
+        .into_iter()
+        .filter_map(|glob| {
+          let error = glob_error(&glob, &options)?;
+          Some(GlobDiagnostic { glob, error })
+        })
+        .collect();
+      (!diagnostics.is_empty()).then_some((key, diagnostics))
+    })
+    .collect()
+}
 
-    true
-  });
+/// Why `glob` fails to compile with these options, if it does
+fn glob_error(glob: &str, options: &WalkOptions) -> Option<String> {
+  if compile_glob("", glob, options).is_some() {
+    return None;
+  }
 
-  walk_builder.sort_by_file_path(|a, b| {
-    if a.is_dir() && b.is_dir() {
-      return a.cmp(b);
-    }
+  match Glob::new(glob) {
+    Err(err) => Some(err.to_string()),
+    // Only extglob translation can fail for globs `globset` accepts
+    Ok(_) => Some(format!("unsupported extglob syntax in '{}'", glob)),
+  }
+}
 
-    if !a.is_dir() && !b.is_dir() {
-      return a.cmp(b);
-    }
+#[test]
+fn test_validate_globs_map() {
+  let globs_map = HashMap::from([
+    (
+      "lock".to_string(),
+      vec!["**/yarn.lock".to_string(), "**/[a.lock".to_string()],
+    ),
+    ("package".to_string(), vec!["**/package.json".to_string()]),
+  ]);
+  let diagnostics = validate_globs_map(globs_map.clone(), None);
+  assert_eq!(diagnostics.len(), 1);
+  let lock = &diagnostics["lock"];
+  assert_eq!(lock.len(), 1);
+  assert_eq!(lock[0].glob, "**/[a.lock");
+  assert!(lock[0].error.contains("unclosed"), "{}", lock[0].error);
+
+  let globs_map = HashMap::from([("ext".to_string(), vec!["!(a).txt".to_string()])]);
+  assert!(validate_globs_map(globs_map.clone(), None).is_empty());
+  let options = Some(WalkOptions {
+    extglob: Some(true),
+    ..Default::default()
+  });
+  let diagnostics = validate_globs_map(globs_map, options);
+  assert!(diagnostics["ext"][0].error.contains("extglob"));
+}
 
-    if a.is_dir() {
-      std::cmp::Ordering::Greater
+/// Groups files by extension, e.g. `ts` or `.ts`, using set lookups instead of globs.
+/// Files having none of the `extensions` are skipped.
+#[napi]
+pub fn walk_repo_by_extension(
+  repo_dir: String,
+  extensions: Vec<String>,
+  options: Option<WalkOptions>,
+) -> HashMap<String, Vec<String>> {
+  let options = options.unwrap_or_default();
+  let case_insensitive = options.case_insensitive == Some(true);
+  let normalize = |ext: &str| {
+    if case_insensitive {
+      ext.to_lowercase()
     } else {
-      std::cmp::Ordering::Less
+      ext.to_string()
     }
-  });
+  };
 
-  walk_builder
-    .build()
-    .filter_map(|entry| {
-      let Ok(entry) = entry else {
-        return None;
-      };
+  let mut keys: HashMap<String, &String> = HashMap::new();
+  for ext in &extensions {
+    keys.insert(normalize(ext.trim_start_matches('.')), ext);
+  }
 
-      let path = entry.path();
-      let Ok(path) = path.strip_prefix(repo_path) else {
-        return None;
-      };
+  let pairs = walk_repo(&repo_dir, &options, |path, _| {
+    let ext = Path::new(path).extension()?.to_str()?;
+    let key = keys.get(&normalize(ext))?;
+    Some((*key, path.to_string()))
+  });
 
-      match path.to_str() {
-        None => None,
-        Some("") => None,
-        Some(_) => f(path),
-      }
-    })
+  let mut accum: HashMap<&String, Vec<String>> =
+    extensions.iter().map(|ext| (ext, vec![])).collect();
+  for (key, path) in pairs {
+    accum.entry(key).or_default().push(path);
+  }
+
+  accum
+    .into_iter()
+    .map(|(key, paths)| (key.to_string(), finish_paths(paths, &options)))
     .collect()
 }
 
-#[napi]
-pub fn glob_to_regex(glob: String) -> Option<String> {
-  let Ok(glob) = Glob::new(&glob) else {
-    return None;
-  };
+#[test]
+fn test_walk_repo_by_extension() {
+  let fixture = make_fixture(&[
+    ".ts",
+    "README.md",
+    "src/index.ts",
+    "src/util.TS",
+    "src/util.test.ts",
+    "src/data.json",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let extensions = vec!["ts".to_string(), ".md".to_string(), "rs".to_string()];
+
+  let paths = walk_repo_by_extension(repo.clone(), extensions.clone(), None);
+  assert_eq!(paths.len(), 3);
+  assert_eq!(
+    paths["ts"],
+    vec!["src/index.ts".to_string(), "src/util.test.ts".to_string()]
+  );
+  assert_eq!(paths[".md"], vec!["README.md".to_string()]);
+  assert!(paths["rs"].is_empty());
 
-  return Some(glob.regex().to_string());
+  let options = Some(WalkOptions {
+    case_insensitive: Some(true),
+    ..Default::default()
+  });
+  let paths = walk_repo_by_extension(repo, extensions, options);
+  assert_eq!(
+    paths["ts"],
+    vec![
+      "src/index.ts".to_string(),
+      "src/util.TS".to_string(),
+      "src/util.test.ts".to_string(),
+    ]
+  );
 }
 
+/// Returns the distinct extensions of matched files, sorted and without the dot.
+/// Only the last extension counts, e.g. `gz` for `a.tar.gz`, and dotfiles such
+/// as `.gitignore` have none. With `caseInsensitive` or `lowercaseOutput`,
+/// extensions are lowercased.
 #[napi]
-pub fn walk_repo_glob(repo_dir: String, glob: String) -> Vec<String> {
-  let Ok(glob) = Glob::new(&glob) else {
+pub fn walk_repo_glob_extensions(
+  repo_dir: String,
+  glob: String,
+  options: Option<WalkOptions>,
+) -> Vec<String> {
+  let options = options.unwrap_or_default();
+  let Some(matcher) = GlobMatcher::single(&repo_dir, &glob, &options) else {
     return vec![];
   };
 
-  let matcher = glob.compile_matcher();
-  walk_repo(&repo_dir, |path| {
-    if matcher.is_match(path) {
-      let path = path.to_str()?;
-      Some(path.to_string())
-    } else {
-      None
+  let lowercase = options.case_insensitive == Some(true) || options.lowercase_output == Some(true);
+  let mut extensions = BTreeSet::new();
+  walk_repo(&repo_dir, &options, |path, _| {
+    if !matcher.is_match(path) {
+      return None;
     }
-  })
+
+    let ext = Path::new(path).extension()?.to_str()?;
+    extensions.insert(if lowercase {
+      ext.to_lowercase()
+    } else {
+      ext.to_string()
+    });
+    None::<()>
+  });
+
+  extensions.into_iter().collect()
 }
 
 #[test]
-pub fn test_walk_repo_glob() {
-  let repo = ".".to_string();
-  let glob = "*.json".to_string();
-  let paths = walk_repo_glob(repo, glob);
-  for path in paths {
-    println!("{}", path);
-  }
+fn test_walk_repo_glob_extensions() {
+  let fixture = make_fixture(&[
+    ".gitignore",
+    "Makefile",
+    "README.md",
+    "docs/guide.md",
+    "dist/app.tar.gz",
+    "src/index.ts",
+    "src/util.TS",
+    "src/data.json",
+  ]);
+  let repo = fixture.path().to_str().unwrap().to_string();
+
+  let extensions = walk_repo_glob_extensions(repo.clone(), "**".to_string(), None);
+  assert_eq!(extensions, vec!["TS", "gz", "json", "md", "ts"]);
+
+  let extensions = walk_repo_glob_extensions(repo.clone(), "src/**".to_string(), None);
+  assert_eq!(extensions, vec!["TS", "json", "ts"]);
+
+  let options = Some(WalkOptions {
+    case_insensitive: Some(true),
+    ..Default::default()
+  });
+  let extensions = walk_repo_glob_extensions(repo, "src/**".to_string(), options);
+  assert_eq!(extensions, vec!["json", "ts"]);
+}
+
+#[napi(object)]
+pub struct RepoSize {
+  pub files: u32,
+  pub dirs: u32,
 }
 
+/// Counts files and directories to be walked without matching or collecting paths,
+/// e.g. for estimating the cost of a scan. The root directory itself isn't counted.
 #[napi]
-pub fn walk_repo_globs(repo_dir: String, globs: Vec<String>) -> Vec<String> {
-  let mut glob_builder = globset::GlobSetBuilder::new();
-  for glob in globs {
-    let Ok(glob) = Glob::new(&glob) else {
+pub fn estimate_repo_size(repo_dir: String, options: Option<WalkOptions>) -> RepoSize {
+  let options = options.unwrap_or_default();
+  let layout = RepoLayout::load(&repo_dir, &options);
+  let walk_builder = build_walker(&repo_dir, None, &options, &layout);
+
+  let mut size = RepoSize { files: 0, dirs: 0 };
+  for entry in walk_builder.build().flatten() {
+    let Some(file_type) = entry.file_type() else {
       continue;
     };
 
-    glob_builder.add(glob);
+    if file_type.is_file() {
+      size.files += 1;
+    } else if file_type.is_dir() && entry.depth() > 0 {
+      size.dirs += 1;
+    }
   }
+  size
+}
 
-  let Ok(matcher) = glob_builder.build() else {
-    return vec![];
-  };
+#[test]
+fn test_estimate_repo_size() {
+  let size = estimate_repo_size(".".to_string(), None);
+  assert!(size.files > 0);
+  assert!(size.dirs > 0);
 
-  walk_repo(&repo_dir, |path| {
-    if matcher.is_match(path) {
-      let path = path.to_str()?;
-      Some(path.to_string())
-    } else {
-      None
-    }
-  })
+  let fixture = make_fixture(&["a.json", "src/b.rs", "src/c/d.rs", "vendor/dep/e.rs"]);
+  std::fs::write(
+    fixture.path().join(".gitmodules"),
+    "[submodule \"dep\"]\n  path = vendor/dep\n",
+  )
+  .unwrap();
+  std::fs::create_dir(fixture.path().join(".git")).unwrap();
+  let size = estimate_repo_size(fixture.path().to_str().unwrap().to_string(), None);
+  assert_eq!(size.files, 4);
+  assert_eq!(size.dirs, 3);
 }
 
-#[test]
-fn test_walk_repo_globs() {
-  let repo = "../renovate".to_string();
-  let globs = vec![
-    "**/package.json".to_string(),
-    "**/package-lock.json".to_string(),
-  ];
-  let paths = walk_repo_globs(repo, globs);
-  for path in paths {
-    println!("{}", path);
+#[napi(object)]
+pub struct WalkDiff {
+  /// Paths present in `b` only
+  pub added: Vec<String>,
+  /// Paths present in `a` only
+  pub removed: Vec<String>,
+}
+
+/// Compares two walk results, returning sorted path lists.
+#[napi]
+pub fn diff_walks(a: Vec<String>, b: Vec<String>) -> WalkDiff {
+  let a: BTreeSet<String> = a.into_iter().collect();
+  let b: BTreeSet<String> = b.into_iter().collect();
+  WalkDiff {
+    added: b.difference(&a).cloned().collect(),
+    removed: a.difference(&b).cloned().collect(),
   }
 }
 
+#[test]
+fn test_diff_walks() {
+  let a = vec!["src/b.rs", "src/a.rs", "README.md"];
+  let b = vec!["src/c.rs", "README.md", "Cargo.toml", "src/a.rs"];
+  let diff = diff_walks(
+    a.into_iter().map(String::from).collect(),
+    b.into_iter().map(String::from).collect(),
+  );
+  assert_eq!(
+    diff.added,
+    vec!["Cargo.toml".to_string(), "src/c.rs".to_string()]
+  );
+  assert_eq!(diff.removed, vec!["src/b.rs".to_string()]);
+}
+
+#[test]
+fn test_diff_walks_disjoint() {
+  let to_strings = |paths: &[&str]| paths.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+  let before = to_strings(&["b.json", "a.json", "a.json"]);
+  let after = to_strings(&["d.json", "c.json"]);
+  let diff = diff_walks(before.clone(), after.clone());
+  assert_eq!(diff.added, vec!["c.json", "d.json"]);
+  assert_eq!(diff.removed, vec!["a.json", "b.json"]);
+
+  let diff = diff_walks(before.clone(), before);
+  assert!(diff.added.is_empty() && diff.removed.is_empty());
+
+  let diff = diff_walks(vec![], after);
+  assert_eq!(diff.added, vec!["c.json", "d.json"]);
+  assert!(diff.removed.is_empty());
+}
+
+/// Walks one repository multiple times, reading `.gitmodules` only once.
+/// Changes to it made afterwards aren't picked up.
 #[napi]
-pub fn walk_repo_globs_map(
+pub struct RepoScanner {
   repo_dir: String,
-  globs_map: HashMap<String, Vec<String>>,
-) -> HashMap<String, Vec<String>> {
-  let mut accum: HashMap<&String, Vec<String>> = HashMap::new();
-  let matchers: Vec<(&String, globset::GlobSet)> = globs_map
-    .iter()
-    .filter_map(|(key, globs)| {
-      accum.insert(key, Vec::new());
+  options: WalkOptions,
+  layout: RepoLayout,
+}
 
-      let mut glob_builder = globset::GlobSetBuilder::new();
-      for glob in globs {
-        let Ok(glob) = Glob::new(glob) else {
-          continue;
-        };
-        glob_builder.add(glob);
-      }
+#[napi]
+impl RepoScanner {
+  #[napi(constructor)]
+  pub fn new(repo_dir: String, options: Option<WalkOptions>) -> Self {
+    let options = options.unwrap_or_default();
+    let layout = RepoLayout::load(&repo_dir, &options);
+    RepoScanner {
+      repo_dir,
+      options,
+      layout,
+    }
+  }
 
-      let Ok(matcher) = glob_builder.build() else {
-        return None;
-      };
+  /// Same as `walkRepoGlob` with the scanner's options
+  #[napi]
+  pub fn walk_glob(&self, glob: String) -> Vec<String> {
+    let Some(matcher) = GlobMatcher::single(&self.repo_dir, &glob, &self.options) else {
+      return vec![];
+    };
 
-      Some((key, matcher))
-    })
-    .collect();
+    self.walk_matching(&matcher)
+  }
 
-  let pairs = walk_repo(&repo_dir, |path: &Path| {
-    let mut matches: Vec<(&String, String)> = vec![];
-    for (key, matcher) in &matchers {
-      if matcher.is_match(path) {
-        let key = *key;
-        let val = path.to_str()?.to_string();
-        matches.push((key, val));
-      }
-    }
-    Some(matches)
-  });
+  /// Same as `walkRepoGlobs` with the scanner's options
+  #[napi]
+  pub fn walk_globs(&self, globs: Vec<String>) -> Vec<String> {
+    let Some(matcher) = GlobMatcher::new(&self.repo_dir, &globs, &self.options) else {
+      return vec![];
+    };
 
-  for (key, path) in pairs.into_iter().flatten() {
-    let entry = accum.entry(key);
-    if let Entry::Occupied(entry) = entry {
-      let paths = entry.into_mut();
-      paths.push(path);
-    }
+    self.walk_matching(&matcher)
   }
 
-  let mut result: HashMap<String, Vec<String>> = HashMap::new();
-  for (key, paths) in accum {
-    result.insert(key.to_string(), paths);
+  fn walk_matching(&self, matcher: &GlobMatcher) -> Vec<String> {
+    let paths = walk_repo_with_layout(
+      &self.repo_dir,
+      None,
+      &self.options,
+      &self.layout,
+      |path, _| {
+        if matcher.is_match(path) {
+          Some(path.to_string())
+        } else {
+          None
+        }
+      },
+    );
+    finish_paths(paths, &self.options)
   }
-  result
 }
 
 #[test]
-fn test_walk_repo_globs_map() {
-  let repo = "../renovate".to_string();
-  let mut globs_map = HashMap::new();
-  globs_map.insert(
-    "package".to_string(),
-    vec![
-      "**/package.json".to_string(),
-      "**/package-lock.json".to_string(),
-    ],
+fn test_repo_scanner() {
+  let fixture = make_fixture(&[
+    "package.json",
+    "src/index.ts",
+    "vendor/dep/package.json",
+    "vendor/dep/index.ts",
+  ]);
+  std::fs::write(
+    fixture.path().join(".gitmodules"),
+    "[submodule \"dep\"]\n  path = vendor/dep\n",
+  )
+  .unwrap();
+  let repo = fixture.path().to_str().unwrap().to_string();
+  let scanner = RepoScanner::new(repo, None);
+
+  // `.gitmodules` isn't read again, so the submodule stays excluded
+  std::fs::remove_file(fixture.path().join(".gitmodules")).unwrap();
+  assert_eq!(
+    scanner.walk_glob("**/package.json".to_string()),
+    vec!["package.json"]
   );
-  globs_map.insert(
-    "lock".to_string(),
-    vec![
-      "**/yarn.lock".to_string(),
-      "**/package-lock.json".to_string(),
-    ],
+  assert_eq!(
+    scanner.walk_globs(vec!["**/*.ts".to_string(), "*.json".to_string()]),
+    vec!["package.json", "src/index.ts"]
   );
-  let paths_map = walk_repo_globs_map(repo, globs_map);
-  for (key, paths) in paths_map {
-    for path in paths {
-      println!("{}: {}", key, path);
-    }
-  }
 }